@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{layer::LayerKind, library::UpdateReport, project::Project, Id};
+
+// error reported by a backend command, attached to the block that caused it if any
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Error {
+    pub block: Option<Id>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(message: impl ToString) -> Self {
+        Self { block: None, message: message.to_string() }
+    }
+
+    pub fn at_block(block: Id, message: impl ToString) -> Self {
+        Self { block: Some(block), message: message.to_string() }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.block {
+            Some(block) => write!(f, "block #{block}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct BlockReport {
+    pub id: Id,
+    pub kind: LayerKind,
+    pub params: i64,
+}
+
+// result of building the board graph into a model
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct CompileReport {
+    pub total_params: i64,
+    pub blocks: Vec<BlockReport>,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub enum SampleInput {
+    // standard normal values in the shape of the Input block
+    Random { batch: i64 },
+    // flat values of a single sample
+    Values(Vec<f64>),
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct BlockStats {
+    // ids of the enclosing groups followed by the id of the block
+    pub path: Vec<Id>,
+    // full output shape, including the batch dimension
+    pub shape: Vec<i64>,
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+// outputs of every block after a forward pass
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardReport {
+    pub blocks: Vec<BlockStats>,
+}
+
+// a project read from a file, with the instances of library components brought up to date
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedProject {
+    pub project: Project,
+    pub update: UpdateReport,
+}
+
+// output of a TorchScript model run on a sample
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceReport {
+    // full output shape, including the batch dimension
+    pub shape: Vec<i64>,
+    // the first values of the first sample
+    pub values: Vec<f64>,
+}
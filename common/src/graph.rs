@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{api, layer::{self, LayerKind}, params::Params, shape::{self, Shape, ShapeError}, Id};
+
+// index into `Vertex::input_ports` or `Vertex::output_ports`
+pub type Port = usize;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: Id,
+    pub from_port: Port,
+    pub to: Id,
+    pub to_port: Port,
+}
+
+impl Edge {
+    pub fn new(from: Id, from_port: Port, to: Id, to_port: Port) -> Self {
+        Self { from, from_port, to, to_port }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Vertex {
+    pub id: Id,
+    pub kind: LayerKind,
+    pub params: Params,
+    // contents of a Group block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<Box<Graph>>,
+}
+
+impl Vertex {
+    pub fn new(id: Id, kind: LayerKind, params: Params) -> Self {
+        Self { id, kind, params, group: None }
+    }
+
+    pub fn input_ports(&self) -> Vec<String> {
+        match &self.group {
+            Some(group) if self.kind == LayerKind::Group => {
+                (0..group.boundary(LayerKind::GroupInput).len()).map(layer::group_input_port).collect()
+            }
+            _ if self.kind == LayerKind::Op => layer::op_input_ports(&self.params),
+            _ => self.kind.input_ports().iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    pub fn output_ports(&self) -> Vec<String> {
+        match self.kind {
+            LayerKind::Op => layer::op_output_ports(&self.params),
+            _ => self.kind.output_ports().iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+// board graph as it is sent between the editor and the backend
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Graph {
+    pub vertices: Vec<Vertex>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    pub fn vertex(&self, id: Id) -> Option<&Vertex> {
+        self.vertices.iter().find(|vertex| vertex.id == id)
+    }
+
+    // ids of the GroupInput or GroupOutput blocks, in id order
+    pub fn boundary(&self, kind: LayerKind) -> Vec<Id> {
+        let mut ids = self.vertices.iter().filter(|vertex| vertex.kind == kind).map(|vertex| vertex.id).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    // ids of the blocks with arrows into the given one, in port order
+    pub fn inputs(&self, id: Id) -> Vec<Id> {
+        let mut edges = self.edges.iter().filter(|edge| edge.to == id).collect::<Vec<_>>();
+        edges.sort_by_key(|edge| edge.to_port);
+        edges.into_iter().map(|edge| edge.from).collect()
+    }
+
+    // every input port has exactly one arrow and every arrow uses existing ports
+    pub fn check_ports(&self) -> Result<(), api::Error> {
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (self.vertex(edge.from), self.vertex(edge.to)) else {
+                return Err(api::Error::new(format!("arrow #{} -> #{} references an unknown block", edge.from, edge.to)));
+            };
+            if edge.from_port >= from.output_ports().len() {
+                return Err(api::Error::at_block(from.id, format!("{} has no output port {}", from.kind, edge.from_port)));
+            }
+            if edge.to_port >= to.input_ports().len() {
+                return Err(api::Error::at_block(to.id, format!("{} has no input port {}", to.kind, edge.to_port)));
+            }
+        }
+        for vertex in &self.vertices {
+            for (port, name) in vertex.input_ports().iter().enumerate() {
+                match self.edges.iter().filter(|edge| edge.to == vertex.id && edge.to_port == port).count() {
+                    0 => return Err(api::Error::at_block(vertex.id, format!("input \"{name}\" is not connected"))),
+                    1 => {}
+                    _ => return Err(api::Error::at_block(vertex.id, format!("input \"{name}\" has several arrows"))),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Kahn's algorithm, blocks on cycles and after them are left out
+    fn partial_order(&self) -> (Vec<&Vertex>, BTreeMap<Id, usize>) {
+        let mut in_degree = self.vertices.iter().map(|vertex| (vertex.id, 0)).collect::<BTreeMap<_, _>>();
+        self.edges.iter().for_each(|edge| *in_degree.entry(edge.to).or_default() += 1);
+        let mut queue = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.vertices.len());
+        while let Some(id) = queue.pop_front() {
+            let Some(vertex) = self.vertex(id) else {
+                continue;
+            };
+            order.push(vertex);
+            self.edges.iter().filter(|edge| edge.from == id).for_each(|edge| {
+                let degree = in_degree.get_mut(&edge.to).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(edge.to);
+                }
+            });
+        }
+        (order, in_degree)
+    }
+
+    pub fn topological_order(&self) -> Result<Vec<&Vertex>, api::Error> {
+        // arrows with a missing end would otherwise look like blocks left on a cycle
+        if let Some(edge) = self.edges.iter().find(|edge| self.vertex(edge.from).is_none()) {
+            return Err(api::Error::at_block(edge.to, format!("arrow from unknown block #{}", edge.from)));
+        }
+        if let Some(edge) = self.edges.iter().find(|edge| self.vertex(edge.to).is_none()) {
+            return Err(api::Error::new(format!("arrow to unknown block #{}", edge.to)));
+        }
+        let (order, in_degree) = self.partial_order();
+        match in_degree.into_iter().find(|(_, degree)| *degree > 0) {
+            Some((id, _)) => Err(api::Error::at_block(id, "block is on a cycle")),
+            None => Ok(order),
+        }
+    }
+
+    // shapes of the blocks whose inputs are all known, as the editor shows them
+    pub fn infer_known_shapes(&self) -> BTreeMap<Id, Result<Shape, ShapeError>> {
+        self.infer_known_shapes_from(&BTreeMap::new())
+    }
+
+    // the same, with the shapes of some blocks given instead of inferred
+    pub fn infer_known_shapes_from(&self, given: &BTreeMap<Id, Shape>) -> BTreeMap<Id, Result<Shape, ShapeError>> {
+        let mut shapes = BTreeMap::<Id, Result<Shape, ShapeError>>::new();
+        for vertex in self.partial_order().0 {
+            if let Some(shape) = given.get(&vertex.id) {
+                shapes.insert(vertex.id, Ok(shape.clone()));
+                continue;
+            }
+            let mut edges = self.edges.iter().filter(|edge| edge.to == vertex.id).collect::<Vec<_>>();
+            edges.sort_by_key(|edge| edge.to_port);
+            let inputs = edges
+                .into_iter()
+                .map(|edge| match shapes.get(&edge.from) {
+                    Some(Ok(shape)) => Some((edge.from, shape.clone())),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            match inputs {
+                Some(inputs) if inputs.len() == vertex.input_ports().len() => {
+                    shapes.insert(vertex.id, shape::infer(vertex.kind, &vertex.params, &inputs));
+                }
+                _ => {}
+            }
+        }
+        shapes
+    }
+
+    pub fn infer_shapes(&self) -> Result<BTreeMap<Id, Shape>, api::Error> {
+        self.check_ports()?;
+        let mut shapes = BTreeMap::<Id, Shape>::new();
+        for vertex in self.topological_order()? {
+            let inputs = self.inputs(vertex.id)
+                .into_iter()
+                .map(|input| (input, shapes[&input].clone()))
+                .collect::<Vec<_>>();
+            let shape = shape::infer(vertex.kind, &vertex.params, &inputs)
+                .map_err(|error| api::Error::at_block(vertex.id, error.message))?;
+            shapes.insert(vertex.id, shape);
+        }
+        Ok(shapes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(id: Id, kind: LayerKind) -> Vertex {
+        Vertex::new(id, kind, Params::defaults(kind))
+    }
+
+    #[test]
+    fn topological_order_reports_unknown_source() {
+        let graph = Graph {
+            vertices: vec![vertex(1, LayerKind::ReLU), vertex(2, LayerKind::Output)],
+            edges: vec![Edge::new(7, 0, 1, 0), Edge::new(1, 0, 2, 0)],
+        };
+        let error = graph.topological_order().unwrap_err();
+        assert_eq!(error.message, "arrow from unknown block #7");
+        assert_eq!(error.block, Some(1));
+    }
+
+    #[test]
+    fn topological_order_reports_cycle() {
+        let graph = Graph {
+            vertices: vec![vertex(1, LayerKind::ReLU), vertex(2, LayerKind::ReLU)],
+            edges: vec![Edge::new(1, 0, 2, 0), Edge::new(2, 0, 1, 0)],
+        };
+        assert_eq!(graph.topological_order().unwrap_err().message, "block is on a cycle");
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+
+use crate::{api, graph::{Edge, Graph, Port, Vertex}, layer::LayerKind, shape::Shape, Id};
+
+// a graph with the groups replaced by their contents
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Flat {
+    pub graph: Graph,
+    // ids of the enclosing groups followed by the id of the block itself, by flat id
+    pub origin: BTreeMap<Id, Vec<Id>>,
+}
+
+// where the arrows attached to a block end up in the flat graph
+#[derive(Default)]
+struct Ports {
+    inputs: Vec<(Id, Port)>,
+    outputs: Vec<(Id, Port)>,
+}
+
+impl Flat {
+    // the top level block containing a flat one
+    pub fn top_level(&self, id: Id) -> Id {
+        self.origin.get(&id).and_then(|path| path.first()).copied().unwrap_or(id)
+    }
+
+    pub fn top_level_error(&self, error: api::Error) -> api::Error {
+        api::Error { block: error.block.map(|id| self.top_level(id)), ..error }
+    }
+
+    fn add_level(&mut self, graph: &Graph, path: &[Id]) -> BTreeMap<Id, Ports> {
+        let mut ports = BTreeMap::new();
+        for vertex in &graph.vertices {
+            let block_path = [path, &[vertex.id]].concat();
+            let block_ports = match (&vertex.group, vertex.kind) {
+                (Some(group), LayerKind::Group) => {
+                    let inner = self.add_level(group, &block_path);
+                    let outputs = match group.boundary(LayerKind::GroupOutput).as_slice() {
+                        [output] => inner[output].outputs.clone(),
+                        // reported by `Graph::check_groups`
+                        _ => Vec::new(),
+                    };
+                    Ports {
+                        inputs: group.boundary(LayerKind::GroupInput).iter().flat_map(|input| inner[input].inputs.clone()).collect(),
+                        outputs,
+                    }
+                }
+                (None, LayerKind::Group) => Ports::default(),
+                _ => {
+                    let id = self.graph.vertices.len() as Id + 1;
+                    // boundary blocks pass the values through the group border
+                    let kind = if vertex.kind.is_group_boundary() { LayerKind::Identity } else { vertex.kind };
+                    let flat = Vertex::new(id, kind, vertex.params.clone());
+                    let block_ports = Ports {
+                        inputs: (0..flat.input_ports().len()).map(|port| (id, port)).collect(),
+                        outputs: (0..flat.output_ports().len()).map(|port| (id, port)).collect(),
+                    };
+                    self.graph.vertices.push(flat);
+                    self.origin.insert(id, block_path);
+                    block_ports
+                }
+            };
+            ports.insert(vertex.id, block_ports);
+        }
+        for edge in &graph.edges {
+            let from = ports.get(&edge.from).and_then(|ports| ports.outputs.get(edge.from_port));
+            let to = ports.get(&edge.to).and_then(|ports| ports.inputs.get(edge.to_port));
+            if let (Some((from, from_port)), Some((to, to_port))) = (from, to) {
+                self.graph.edges.push(Edge::new(*from, *from_port, *to, *to_port));
+            }
+        }
+        ports
+    }
+}
+
+impl Graph {
+    pub fn flatten(&self) -> Flat {
+        let mut flat = Flat::default();
+        flat.add_level(self, &[]);
+        flat
+    }
+
+    // shape coming out of a group whose ports get the given shapes, None while it can not be told
+    pub fn group_output_shape(&self, inputs: &[Option<Shape>]) -> Option<Shape> {
+        let flat = self.flatten();
+        let flat_id = |id: Id| flat.origin.iter().find(|(_, path)| path.as_slice() == [id]).map(|(flat_id, _)| *flat_id);
+        let given = self
+            .boundary(LayerKind::GroupInput)
+            .into_iter()
+            .zip(inputs)
+            .filter_map(|(input, shape)| Some((flat_id(input)?, shape.clone()?)))
+            .collect();
+        let output = match self.boundary(LayerKind::GroupOutput).as_slice() {
+            [output] => flat_id(*output)?,
+            _ => return None,
+        };
+        flat.graph.infer_known_shapes_from(&given).remove(&output)?.ok()
+    }
+
+    // boundary blocks only make sense inside groups, and a group has a single output
+    pub fn check_groups(&self) -> Result<(), api::Error> {
+        self.check_level(true)
+    }
+
+    fn check_level(&self, top: bool) -> Result<(), api::Error> {
+        for vertex in &self.vertices {
+            if top && vertex.kind.is_group_boundary() {
+                return Err(api::Error::at_block(vertex.id, format!("{} is outside of a group", vertex.kind)));
+            }
+            if vertex.kind != LayerKind::Group {
+                continue;
+            }
+            let Some(group) = &vertex.group else {
+                return Err(api::Error::at_block(vertex.id, "the group is empty"));
+            };
+            if group.boundary(LayerKind::GroupOutput).len() != 1 {
+                return Err(api::Error::at_block(vertex.id, "a group needs exactly one GroupOutput block"));
+            }
+            // errors inside are reported on the group seen from this level
+            group.check_level(false).map_err(|error| api::Error::at_block(vertex.id, error.message))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::Params;
+
+    fn vertex(id: Id, kind: LayerKind) -> Vertex {
+        Vertex::new(id, kind, Params::defaults(kind))
+    }
+
+    // GroupInput -> ReLU -> GroupOutput
+    fn relu_group() -> Graph {
+        Graph {
+            vertices: vec![vertex(1, LayerKind::GroupInput), vertex(2, LayerKind::ReLU), vertex(3, LayerKind::GroupOutput)],
+            edges: vec![Edge::new(1, 0, 2, 0), Edge::new(2, 0, 3, 0)],
+        }
+    }
+
+    // Input -> group -> Output
+    fn board(group: Graph) -> Graph {
+        let mut group_vertex = vertex(2, LayerKind::Group);
+        group_vertex.group = Some(Box::new(group));
+        Graph {
+            vertices: vec![vertex(1, LayerKind::Input), group_vertex, vertex(3, LayerKind::Output)],
+            edges: vec![Edge::new(1, 0, 2, 0), Edge::new(2, 0, 3, 0)],
+        }
+    }
+
+    #[test]
+    fn flatten_wires_through_the_group() {
+        let flat = board(relu_group()).flatten();
+        let kinds = flat.graph.vertices.iter().map(|vertex| vertex.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [LayerKind::Input, LayerKind::Identity, LayerKind::ReLU, LayerKind::Identity, LayerKind::Output]);
+        assert_eq!(flat.origin[&3], vec![2, 2]);
+        assert_eq!(flat.top_level(3), 2);
+        assert_eq!(flat.graph.edges, [Edge::new(2, 0, 3, 0), Edge::new(3, 0, 4, 0), Edge::new(1, 0, 2, 0), Edge::new(4, 0, 5, 0)]);
+        assert!(flat.graph.topological_order().is_ok());
+    }
+
+    #[test]
+    fn flatten_skips_empty_groups() {
+        let mut graph = board(Graph::default());
+        graph.vertices[1].group = None;
+        let flat = graph.flatten();
+        assert_eq!(flat.graph.vertices.len(), 2);
+        assert!(flat.graph.edges.is_empty());
+    }
+
+    #[test]
+    fn check_groups_accepts_a_valid_group() {
+        assert!(board(relu_group()).check_groups().is_ok());
+    }
+
+    #[test]
+    fn check_groups_rejects_boundary_outside_of_group() {
+        let graph = Graph { vertices: vec![vertex(1, LayerKind::GroupInput)], edges: Vec::new() };
+        assert_eq!(graph.check_groups().unwrap_err().block, Some(1));
+    }
+
+    #[test]
+    fn check_groups_reports_inner_errors_at_the_group() {
+        let mut group = relu_group();
+        group.vertices.pop();
+        let error = board(group).check_groups().unwrap_err();
+        assert_eq!(error.block, Some(2));
+        assert_eq!(error.message, "a group needs exactly one GroupOutput block");
+    }
+
+    #[test]
+    fn group_output_shape_follows_the_inputs() {
+        let group = relu_group();
+        assert_eq!(group.group_output_shape(&[Some(Shape(vec![3, 4]))]), Some(Shape(vec![3, 4])));
+        assert_eq!(group.group_output_shape(&[None]), None);
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::params::{self, ParamSpec, Params};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LayerKind {
+    Input,
+    Linear,
+    Conv2d,
+    MaxPool2d,
+    BatchNorm,
+    ReLU,
+    LeakyReLU,
+    Dropout,
+    Flatten,
+    Softmax,
+    Add,
+    Concat,
+    Identity,
+    // an imported onnx node with no matching layer, its params keep the op type and the attributes
+    Op,
+    // holds a nested graph, its ports are the boundary blocks inside
+    Group,
+    GroupInput,
+    GroupOutput,
+    Output,
+}
+
+impl LayerKind {
+    pub const ALL: &'static [LayerKind] = &[
+        Self::Input,
+        Self::Linear,
+        Self::Conv2d,
+        Self::MaxPool2d,
+        Self::BatchNorm,
+        Self::ReLU,
+        Self::LeakyReLU,
+        Self::Dropout,
+        Self::Flatten,
+        Self::Softmax,
+        Self::Add,
+        Self::Concat,
+        Self::Identity,
+        Self::Op,
+        Self::Group,
+        Self::GroupInput,
+        Self::GroupOutput,
+        Self::Output,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Input => "Input",
+            Self::Linear => "Linear",
+            Self::Conv2d => "Conv2d",
+            Self::MaxPool2d => "MaxPool2d",
+            Self::BatchNorm => "BatchNorm",
+            Self::ReLU => "ReLU",
+            Self::LeakyReLU => "LeakyReLU",
+            Self::Dropout => "Dropout",
+            Self::Flatten => "Flatten",
+            Self::Softmax => "Softmax",
+            Self::Add => "Add",
+            Self::Concat => "Concat",
+            Self::Identity => "Identity",
+            Self::Op => "Op",
+            Self::Group => "Group",
+            Self::GroupInput => "GroupInput",
+            Self::GroupOutput => "GroupOutput",
+            Self::Output => "Output",
+        }
+    }
+
+    // names of the input ports, an edge refers to a port by its index here
+    pub fn input_ports(&self) -> &'static [&'static str] {
+        match self {
+            // group and op ports depend on the block, see `graph::Vertex::input_ports`
+            Self::Input | Self::GroupInput | Self::Group | Self::Op => &[],
+            // the order of the ports is the order of the concatenation
+            Self::Add | Self::Concat => &["a", "b"],
+            _ => &["in"],
+        }
+    }
+
+    pub fn output_ports(&self) -> &'static [&'static str] {
+        match self {
+            Self::Output | Self::GroupOutput | Self::Op => &[],
+            _ => &["out"],
+        }
+    }
+
+    // blocks that stand for the ports of the group they are in
+    pub fn is_group_boundary(&self) -> bool {
+        matches!(self, Self::GroupInput | Self::GroupOutput)
+    }
+
+    pub fn param_specs(&self) -> &'static [ParamSpec] {
+        match self {
+            Self::Input => params::INPUT,
+            Self::Linear => params::LINEAR,
+            Self::Conv2d => params::CONV2D,
+            Self::MaxPool2d => params::MAX_POOL2D,
+            Self::BatchNorm => params::BATCH_NORM,
+            Self::LeakyReLU => params::LEAKY_RELU,
+            Self::Dropout => params::DROPOUT,
+            Self::Softmax => params::SOFTMAX,
+            Self::Concat => params::CONCAT,
+            // the attributes of an Op block are shown but not edited
+            Self::ReLU
+            | Self::Flatten
+            | Self::Add
+            | Self::Identity
+            | Self::Op
+            | Self::Group
+            | Self::GroupInput
+            | Self::GroupOutput
+            | Self::Output => params::NONE,
+        }
+    }
+}
+
+// a group has an input port for every GroupInput block inside it, in id order
+pub fn group_input_port(index: usize) -> String {
+    format!("in {}", index + 1)
+}
+
+// an Op block has the ports of the imported node, their counts are kept in the params
+pub fn op_input_ports(params: &Params) -> Vec<String> {
+    (1..=params.int(params::OP_INPUTS).unwrap_or_default()).map(|index| format!("in {index}")).collect()
+}
+
+pub fn op_output_ports(params: &Params) -> Vec<String> {
+    (1..=params.int(params::OP_OUTPUTS).unwrap_or_default()).map(|index| format!("out {index}")).collect()
+}
+
+impl std::fmt::Display for LayerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{graph::Edge, Id};
+
+// distances between the centers of neighbouring blocks
+pub const COLUMN_SPACING: f64 = 250.;
+pub const ROW_SPACING: f64 = 200.;
+// passes over the columns, the order with the fewest crossings is kept
+const ORDER_SWEEPS: usize = 8;
+const POSITION_SWEEPS: usize = 4;
+
+// length of the longest path of arrows leading to every block, blocks on cycles keep the length up to the cycle
+fn ranks(ids: &[Id], edges: &[Edge]) -> BTreeMap<Id, usize> {
+    let mut ranks = ids.iter().map(|id| (*id, 0)).collect::<BTreeMap<_, usize>>();
+    let mut in_degree = ids.iter().map(|id| (*id, 0)).collect::<BTreeMap<_, usize>>();
+    edges.iter().for_each(|edge| *in_degree.entry(edge.to).or_default() += 1);
+    let mut queue = in_degree.iter().filter(|(_, degree)| **degree == 0).map(|(id, _)| *id).collect::<VecDeque<_>>();
+    while let Some(id) = queue.pop_front() {
+        let rank = ranks[&id] + 1;
+        for edge in edges.iter().filter(|edge| edge.from == id) {
+            let to = ranks.entry(edge.to).or_default();
+            *to = (*to).max(rank);
+            let degree = in_degree.get_mut(&edge.to).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(edge.to);
+            }
+        }
+    }
+    // blocks without inputs, like constants, go right before the first block using them
+    for id in ids {
+        let used_at = edges.iter().filter(|edge| edge.from == *id).map(|edge| ranks[&edge.to]).min();
+        if let Some(rank) = used_at.filter(|_| !edges.iter().any(|edge| edge.to == *id)) {
+            ranks.insert(*id, rank.saturating_sub(1));
+        }
+    }
+    ranks
+}
+
+// blocks and the dummy nodes arrows spanning several columns pass through, split into columns
+struct Layers {
+    // the block of every node, None for dummy nodes
+    nodes: Vec<Option<Id>>,
+    // column of every node
+    ranks: Vec<usize>,
+    // node indices of every column, top to bottom
+    columns: Vec<Vec<usize>>,
+    // links between nodes of neighbouring columns, from left to right
+    links: Vec<(usize, usize)>,
+}
+
+impl Layers {
+    fn new(ids: &[Id], edges: &[Edge]) -> Self {
+        let ranks = ranks(ids, edges);
+        let mut layers = Self { nodes: Vec::new(), ranks: Vec::new(), columns: Vec::new(), links: Vec::new() };
+        let mut index = BTreeMap::new();
+        for (id, rank) in &ranks {
+            index.insert(*id, layers.add_node(Some(*id), *rank));
+        }
+        // arrows back into a cycle do not take part in the layout
+        for edge in edges.iter().filter(|edge| ranks[&edge.from] < ranks[&edge.to]) {
+            let mut from = index[&edge.from];
+            for rank in ranks[&edge.from] + 1..ranks[&edge.to] {
+                let dummy = layers.add_node(None, rank);
+                layers.links.push((from, dummy));
+                from = dummy;
+            }
+            layers.links.push((from, index[&edge.to]));
+        }
+        // moving the sources may leave a column empty
+        layers.columns.retain(|column| !column.is_empty());
+        layers
+    }
+
+    fn add_node(&mut self, id: Option<Id>, rank: usize) -> usize {
+        self.nodes.push(id);
+        self.ranks.push(rank);
+        if self.columns.len() <= rank {
+            self.columns.resize(rank + 1, Vec::new());
+        }
+        self.columns[rank].push(self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn neighbours(&self, node: usize, forward: bool) -> impl Iterator<Item = usize> + '_ {
+        self.links.iter().filter_map(move |(from, to)| match forward {
+            true => (*to == node).then_some(*from),
+            false => (*from == node).then_some(*to),
+        })
+    }
+
+    fn crossings(&self, rows: &[f64]) -> usize {
+        let mut count = 0;
+        for (index, (a, b)) in self.links.iter().enumerate() {
+            for (c, d) in &self.links[index + 1..] {
+                if self.ranks[*a] == self.ranks[*c] && (rows[*a] - rows[*c]) * (rows[*b] - rows[*d]) < 0. {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn rows(&self) -> Vec<f64> {
+        let mut rows = vec![0.; self.nodes.len()];
+        for column in &self.columns {
+            column.iter().enumerate().for_each(|(row, node)| rows[*node] = row as f64);
+        }
+        rows
+    }
+
+    // a node goes to the average row of its neighbours in the column the sweep came from
+    fn order(&mut self) {
+        let mut best = (self.crossings(&self.rows()), self.columns.clone());
+        for sweep in 0..ORDER_SWEEPS {
+            let forward = sweep % 2 == 0;
+            let ranks = (0..self.columns.len()).collect::<Vec<_>>();
+            let ranks = if forward { ranks } else { ranks.into_iter().rev().collect() };
+            for rank in ranks {
+                let rows = self.rows();
+                let mut keyed = self.columns[rank]
+                    .iter()
+                    .map(|node| {
+                        let neighbours = self.neighbours(*node, forward).map(|neighbour| rows[neighbour]).collect::<Vec<_>>();
+                        match neighbours.is_empty() {
+                            true => (rows[*node], *node),
+                            false => (neighbours.iter().sum::<f64>() / neighbours.len() as f64, *node),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                self.columns[rank] = keyed.into_iter().map(|(_, node)| node).collect();
+            }
+            let crossings = self.crossings(&self.rows());
+            if crossings < best.0 {
+                best = (crossings, self.columns.clone());
+            }
+        }
+        self.columns = best.1;
+    }
+
+    // vertical centers, nodes are pulled towards their neighbours and kept a row apart
+    fn positions(&self) -> Vec<f64> {
+        let mut ys = vec![0.; self.nodes.len()];
+        for column in &self.columns {
+            let offset = (column.len() as f64 - 1.) / 2.;
+            column.iter().enumerate().for_each(|(row, node)| ys[*node] = (row as f64 - offset) * ROW_SPACING);
+        }
+        for sweep in 0..POSITION_SWEEPS {
+            let forward = sweep % 2 == 0;
+            let columns = self.columns.iter().collect::<Vec<_>>();
+            let columns = if forward { columns } else { columns.into_iter().rev().collect() };
+            for column in columns {
+                let wanted = column
+                    .iter()
+                    .map(|node| {
+                        let neighbours = self.neighbours(*node, forward).map(|neighbour| ys[neighbour]).collect::<Vec<_>>();
+                        match neighbours.is_empty() {
+                            true => ys[*node],
+                            false => neighbours.iter().sum::<f64>() / neighbours.len() as f64,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let mut placed = Vec::with_capacity(column.len());
+                for (row, y) in wanted.iter().enumerate() {
+                    placed.push(match row {
+                        0 => *y,
+                        _ => y.max(placed[row - 1] + ROW_SPACING),
+                    });
+                }
+                // pushing down only moves the column off, so it is shifted back by the average of that
+                let shift = placed.iter().zip(&wanted).map(|(y, wanted)| y - wanted).sum::<f64>() / column.len() as f64;
+                column.iter().zip(placed).for_each(|(node, y)| ys[*node] = y - shift);
+            }
+        }
+        ys
+    }
+}
+
+// centers of the blocks of a graph flowing left to right: a column for every step along the arrows,
+// ordered to cross few arrows and placed close to the blocks they are connected to
+pub fn layered(ids: &[Id], edges: &[Edge]) -> BTreeMap<Id, (f64, f64)> {
+    // arrows to blocks left out, like the ones around a selection, are not followed
+    let known = ids.iter().collect::<BTreeSet<_>>();
+    let edges = edges.iter().filter(|edge| known.contains(&edge.from) && known.contains(&edge.to)).copied().collect::<Vec<_>>();
+    let mut layers = Layers::new(ids, &edges);
+    layers.order();
+    let ys = layers.positions();
+    let mut centers = BTreeMap::new();
+    for (rank, column) in layers.columns.iter().enumerate() {
+        for node in column {
+            if let Some(id) = layers.nodes[*node] {
+                centers.insert(id, (rank as f64 * COLUMN_SPACING, ys[*node]));
+            }
+        }
+    }
+    centers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(Id, Id)]) -> Vec<Edge> {
+        pairs.iter().map(|(from, to)| Edge::new(*from, 0, *to, 0)).collect()
+    }
+
+    #[test]
+    fn chain_goes_left_to_right_in_a_row() {
+        let centers = layered(&[1, 2, 3], &edges(&[(1, 2), (2, 3)]));
+        assert_eq!(centers[&1], (0., 0.));
+        assert_eq!(centers[&2], (COLUMN_SPACING, 0.));
+        assert_eq!(centers[&3], (2. * COLUMN_SPACING, 0.));
+    }
+
+    #[test]
+    fn diamond_has_no_crossings() {
+        let edges = edges(&[(1, 2), (1, 3), (2, 4), (3, 4)]);
+        let mut layers = Layers::new(&[1, 2, 3, 4], &edges);
+        layers.order();
+        assert_eq!(layers.crossings(&layers.rows()), 0);
+        let centers = layered(&[1, 2, 3, 4], &edges);
+        assert_eq!(centers[&2].0, centers[&3].0);
+        assert!((centers[&2].1 - centers[&3].1).abs() >= ROW_SPACING);
+        // the ends sit between the two branches
+        assert_eq!(centers[&1].1, centers[&4].1);
+    }
+
+    #[test]
+    fn long_edge_passes_through_a_dummy_node() {
+        let edges = edges(&[(1, 2), (2, 3), (1, 3)]);
+        let layers = Layers::new(&[1, 2, 3], &edges);
+        assert_eq!(layers.nodes.iter().filter(|node| node.is_none()).count(), 1);
+        assert_eq!(layers.links.len(), 4);
+        let centers = layered(&[1, 2, 3], &edges);
+        assert_eq!(centers.len(), 3);
+        assert_eq!(centers[&3].0, 2. * COLUMN_SPACING);
+    }
+
+    #[test]
+    fn cycle_terminates() {
+        let centers = layered(&[1, 2, 3, 4], &edges(&[(1, 2), (2, 3), (3, 2), (3, 4)]));
+        assert_eq!(centers.len(), 4);
+        assert!(centers.values().all(|(x, y)| x.is_finite() && y.is_finite()));
+    }
+
+    #[test]
+    fn arrows_to_left_out_blocks_are_ignored() {
+        let centers = layered(&[1, 2], &edges(&[(1, 2), (2, 9), (8, 1)]));
+        assert_eq!(centers.len(), 2);
+        assert_eq!(centers[&2], (COLUMN_SPACING, 0.));
+    }
+}
@@ -0,0 +1,12 @@
+// types shared by the editor frontend and the tauri backend
+pub mod api;
+pub mod graph;
+pub mod group;
+pub mod layer;
+pub mod layout;
+pub mod library;
+pub mod params;
+pub mod project;
+pub mod shape;
+
+pub type Id = i64;
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{graph::Edge, project::{Block, Group, Project}, Id};
+
+// a named subgraph shared between projects
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    // bumped on every save, instances made from an older one get updated on load
+    pub version: u32,
+    pub group: Group,
+}
+
+// what a Group block remembers about the component it was made from
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentRef {
+    pub name: String,
+    pub version: u32,
+}
+
+impl Component {
+    pub fn reference(&self) -> ComponentRef {
+        ComponentRef { name: self.name.clone(), version: self.version }
+    }
+}
+
+// an arrow into a port the updated component no longer has
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedArrow {
+    // ids of the groups the arrow was in, outermost first
+    pub path: Vec<Id>,
+    pub edge: Edge,
+}
+
+// what bringing the instances up to the library versions changed
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateReport {
+    // names of the updated components
+    pub updated: Vec<String>,
+    pub dropped: Vec<DroppedArrow>,
+}
+
+impl UpdateReport {
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.dropped.is_empty()
+    }
+}
+
+// names end up in file names, so only plain characters are allowed
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ')
+}
+
+impl Project {
+    // replaces the contents of instances older than the library ones
+    pub fn update_components(&mut self, library: &BTreeMap<String, Component>) -> UpdateReport {
+        let mut report = UpdateReport::default();
+        update_level(&mut self.blocks, &mut self.edges, library, &[], &mut report);
+        report.updated.sort();
+        report.updated.dedup();
+        report
+    }
+}
+
+fn update_level(blocks: &mut [Block], edges: &mut Vec<Edge>, library: &BTreeMap<String, Component>, path: &[Id], report: &mut UpdateReport) {
+    for block in blocks.iter_mut() {
+        let latest = block.component.as_ref().and_then(|component| library.get(&component.name).filter(|latest| latest.version > component.version));
+        if let Some(latest) = latest {
+            block.group = Some(latest.group.clone());
+            block.component = Some(latest.reference());
+            report.updated.push(latest.name.clone());
+        }
+        if let Some(group) = &mut block.group {
+            update_level(&mut group.blocks, &mut group.edges, library, &[path, &[block.id]].concat(), report);
+        }
+    }
+    // the new definition may have fewer input ports
+    edges.retain(|edge| {
+        let kept = blocks.iter().find(|block| block.id == edge.to).is_some_and(|block| edge.to_port < block.input_port_count());
+        if !kept {
+            report.dropped.push(DroppedArrow { path: path.to_vec(), edge: *edge });
+        }
+        kept
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{layer::LayerKind, params::Params, project::Viewbox};
+
+    fn block(id: Id, kind: LayerKind) -> Block {
+        Block { id, kind, params: Params::defaults(kind), x: 0., y: 0., group: None, component: None }
+    }
+
+    fn instance(id: Id, name: &str, version: u32, group: Group) -> Block {
+        Block { group: Some(group), component: Some(ComponentRef { name: name.to_string(), version }), ..block(id, LayerKind::Group) }
+    }
+
+    // a group passing as many inputs as given through Add or ReLU
+    fn group(inputs: usize) -> Group {
+        let mut blocks = (1..=inputs as Id).map(|id| block(id, LayerKind::GroupInput)).collect::<Vec<_>>();
+        let inner = match inputs {
+            2 => LayerKind::Add,
+            _ => LayerKind::ReLU,
+        };
+        blocks.push(block(10, inner));
+        blocks.push(block(11, LayerKind::GroupOutput));
+        let mut edges = (1..=inputs as Id).map(|id| Edge::new(id, 0, 10, id as usize - 1)).collect::<Vec<_>>();
+        edges.push(Edge::new(10, 0, 11, 0));
+        Group { last_id: 11, blocks, edges }
+    }
+
+    fn project(blocks: Vec<Block>, edges: Vec<Edge>) -> Project {
+        Project { version: crate::project::VERSION, last_id: 10, viewbox: Viewbox { x: 0., y: 0., scale: 1. }, blocks, edges }
+    }
+
+    fn library(components: &[(&str, u32, Group)]) -> BTreeMap<String, Component> {
+        components
+            .iter()
+            .map(|(name, version, group)| (name.to_string(), Component { name: name.to_string(), version: *version, group: group.clone() }))
+            .collect()
+    }
+
+    #[test]
+    fn update_components_replaces_older_versions() {
+        let mut project = project(vec![instance(1, "pair", 1, group(2)), instance(2, "pair", 2, group(2))], Vec::new());
+        let report = project.update_components(&library(&[("pair", 2, group(1))]));
+        assert_eq!(report.updated, ["pair"]);
+        assert_eq!(project.blocks[0].component.as_ref().unwrap().version, 2);
+        assert_eq!(project.blocks[0].group, Some(group(1)));
+        // already up to date
+        assert_eq!(project.blocks[1].group, Some(group(2)));
+    }
+
+    #[test]
+    fn update_components_updates_nested_instances() {
+        let mut outer = group(1);
+        outer.blocks.push(instance(5, "inner", 1, group(1)));
+        let mut project = project(vec![instance(1, "outer", 1, outer)], Vec::new());
+        let report = project.update_components(&library(&[("inner", 3, group(2))]));
+        assert_eq!(report.updated, ["inner"]);
+        let nested = &project.blocks[0].group.as_ref().unwrap().blocks[3];
+        assert_eq!(nested.component, Some(ComponentRef { name: "inner".to_string(), version: 3 }));
+        assert_eq!(nested.group, Some(group(2)));
+    }
+
+    #[test]
+    fn update_components_reports_arrows_into_removed_ports() {
+        let edges = vec![Edge::new(2, 0, 1, 0), Edge::new(3, 0, 1, 1)];
+        let mut project = project(vec![instance(1, "pair", 1, group(2)), block(2, LayerKind::Input), block(3, LayerKind::Input)], edges);
+        let report = project.update_components(&library(&[("pair", 2, group(1))]));
+        assert_eq!(project.edges, [Edge::new(2, 0, 1, 0)]);
+        assert_eq!(report.dropped, [DroppedArrow { path: Vec::new(), edge: Edge::new(3, 0, 1, 1) }]);
+    }
+}
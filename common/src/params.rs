@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::layer::LayerKind;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+    Dims(Vec<i64>),
+    // only set on Op blocks, for the op type and string attributes
+    Text(String),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ParamType {
+    Int { min: i64, max: i64, default: i64 },
+    Float { min: f64, max: f64, default: f64 },
+    // comma separated list of positive sizes, e.g. "3, 32, 32"
+    Dims { default: &'static [i64] },
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub ty: ParamType,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParamError {
+    NotAnInteger,
+    NotANumber,
+    IntOutOfRange { min: i64, max: i64 },
+    FloatOutOfRange { min: f64, max: f64 },
+    InvalidDims,
+}
+
+const MAX_FEATURES: i64 = 1 << 20;
+const MAX_WINDOW: i64 = 64;
+
+const fn int(name: &'static str, label: &'static str, min: i64, max: i64, default: i64) -> ParamSpec {
+    ParamSpec { name, label, ty: ParamType::Int { min, max, default } }
+}
+const fn float(name: &'static str, label: &'static str, min: f64, max: f64, default: f64) -> ParamSpec {
+    ParamSpec { name, label, ty: ParamType::Float { min, max, default } }
+}
+
+pub const INPUT: &[ParamSpec] = &[
+    ParamSpec { name: "shape", label: "Shape", ty: ParamType::Dims { default: &[1, 28, 28] } },
+];
+pub const LINEAR: &[ParamSpec] = &[
+    int("in_features", "In features", 1, MAX_FEATURES, 784),
+    int("out_features", "Out features", 1, MAX_FEATURES, 128),
+];
+pub const CONV2D: &[ParamSpec] = &[
+    int("in_channels", "In channels", 1, MAX_FEATURES, 1),
+    int("out_channels", "Out channels", 1, MAX_FEATURES, 16),
+    int("kernel_size", "Kernel size", 1, MAX_WINDOW, 3),
+    int("stride", "Stride", 1, MAX_WINDOW, 1),
+    int("padding", "Padding", 0, MAX_WINDOW, 0),
+];
+pub const MAX_POOL2D: &[ParamSpec] = &[
+    int("kernel_size", "Kernel size", 1, MAX_WINDOW, 2),
+    int("stride", "Stride", 1, MAX_WINDOW, 2),
+    int("padding", "Padding", 0, MAX_WINDOW, 0),
+];
+pub const BATCH_NORM: &[ParamSpec] = &[
+    int("num_features", "Features", 1, MAX_FEATURES, 16),
+];
+pub const LEAKY_RELU: &[ParamSpec] = &[
+    float("negative_slope", "Negative slope", 0.0, 1.0, 0.01),
+];
+pub const DROPOUT: &[ParamSpec] = &[
+    float("p", "Probability", 0.0, 1.0, 0.5),
+];
+pub const SOFTMAX: &[ParamSpec] = &[
+    int("dim", "Dimension", -8, 8, -1),
+];
+pub const CONCAT: &[ParamSpec] = &[
+    int("dim", "Dimension", -8, 8, 1),
+];
+pub const NONE: &[ParamSpec] = &[];
+
+// params of an Op block besides the attributes of the node, no onnx operator has attributes named so
+pub const OP_TYPE: &str = "op_type";
+pub const OP_INPUTS: &str = "input_count";
+pub const OP_OUTPUTS: &str = "output_count";
+
+impl ParamSpec {
+    pub fn default_value(&self) -> ParamValue {
+        match self.ty {
+            ParamType::Int { default, .. } => ParamValue::Int(default),
+            ParamType::Float { default, .. } => ParamValue::Float(default),
+            ParamType::Dims { default } => ParamValue::Dims(default.to_vec()),
+        }
+    }
+
+    pub fn parse(&self, raw: &str) -> Result<ParamValue, ParamError> {
+        let raw = raw.trim();
+        match self.ty {
+            ParamType::Int { min, max, .. } => {
+                let value = raw.parse::<i64>().map_err(|_| ParamError::NotAnInteger)?;
+                match (min..=max).contains(&value) {
+                    true => Ok(ParamValue::Int(value)),
+                    false => Err(ParamError::IntOutOfRange { min, max }),
+                }
+            }
+            ParamType::Float { min, max, .. } => {
+                let value = raw.parse::<f64>().map_err(|_| ParamError::NotANumber)?;
+                match (min..=max).contains(&value) {
+                    true => Ok(ParamValue::Float(value)),
+                    false => Err(ParamError::FloatOutOfRange { min, max }),
+                }
+            }
+            ParamType::Dims { .. } => {
+                let dims = raw
+                    .split(',')
+                    .map(|dim| dim.trim().parse::<i64>().ok().filter(|dim| *dim > 0))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(ParamError::InvalidDims)?;
+                Ok(ParamValue::Dims(dims))
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Params(BTreeMap<String, ParamValue>);
+
+impl Params {
+    pub fn defaults(kind: LayerKind) -> Self {
+        Self(kind.param_specs().iter().map(|spec| (spec.name.to_string(), spec.default_value())).collect())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ParamValue> {
+        self.0.get(name)
+    }
+
+    pub fn set(&mut self, name: &str, value: ParamValue) {
+        self.0.insert(name.to_string(), value);
+    }
+
+    pub fn int(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(ParamValue::Int(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn float(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(ParamValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn dims(&self, name: &str) -> Option<&[i64]> {
+        match self.get(name) {
+            Some(ParamValue::Dims(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(ParamValue::Text(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ParamValue)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+impl std::fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Dims(dims) => write!(f, "{}", dims.iter().map(|dim| dim.to_string()).collect::<Vec<_>>().join(", ")),
+            Self::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAnInteger => write!(f, "should be an integer"),
+            Self::NotANumber => write!(f, "should be a number"),
+            Self::IntOutOfRange { min, max } => write!(f, "should be in range [{min}, {max}]"),
+            Self::FloatOutOfRange { min, max } => write!(f, "should be in range [{min}, {max}]"),
+            Self::InvalidDims => write!(f, "should be a comma separated list of positive integers"),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{api, graph::Edge, layer::{self, LayerKind}, library::ComponentRef, params::Params, Id};
+
+// bumped on every incompatible change of the format
+pub const VERSION: u32 = 4;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub id: Id,
+    pub kind: LayerKind,
+    pub params: Params,
+    // center of the block on the board
+    pub x: f64,
+    pub y: f64,
+    // contents of a Group block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<Group>,
+    // library component the group was made from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<ComponentRef>,
+}
+
+// nested graph of a Group block, ids are only unique inside of it
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+    pub last_id: Id,
+    pub blocks: Vec<Block>,
+    pub edges: Vec<Edge>,
+}
+
+impl Block {
+    pub fn input_port_count(&self) -> usize {
+        match &self.group {
+            Some(group) if self.kind == LayerKind::Group => {
+                group.blocks.iter().filter(|block| block.kind == LayerKind::GroupInput).count()
+            }
+            _ if self.kind == LayerKind::Op => layer::op_input_ports(&self.params).len(),
+            _ => self.kind.input_ports().len(),
+        }
+    }
+
+    pub fn output_port_count(&self) -> usize {
+        match self.kind {
+            LayerKind::Op => layer::op_output_ports(&self.params).len(),
+            _ => self.kind.output_ports().len(),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Viewbox {
+    // top left corner on the board
+    pub x: f64,
+    pub y: f64,
+    pub scale: f64,
+}
+
+// everything needed to restore the board, stored as json
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    // last id given to a block, new blocks continue after it
+    pub last_id: Id,
+    pub viewbox: Viewbox,
+    pub blocks: Vec<Block>,
+    pub edges: Vec<Edge>,
+}
+
+// blocks copied to the clipboard with the arrows between them
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fragment {
+    pub blocks: Vec<Block>,
+    pub edges: Vec<Edge>,
+}
+
+// checks the references a hand edited or broken file could get wrong
+fn validate_level(last_id: Id, blocks: &[Block], edges: &[Edge]) -> Result<(), api::Error> {
+    let mut by_id = BTreeMap::new();
+    for block in blocks {
+        if by_id.insert(block.id, block).is_some() {
+            return Err(api::Error::at_block(block.id, format!("duplicate block id {}", block.id)));
+        }
+        if block.id > last_id {
+            return Err(api::Error::at_block(block.id, format!("block id {} is above the last id {}", block.id, last_id)));
+        }
+        if let Some(group) = &block.group {
+            validate_level(group.last_id, &group.blocks, &group.edges)
+                .map_err(|error| api::Error::at_block(block.id, format!("in the group: {}", error.message)))?;
+        }
+    }
+    for edge in edges {
+        let blocks = by_id.get(&edge.from).zip(by_id.get(&edge.to));
+        let valid = |(from, to): (&&Block, &&Block)| {
+            edge.from_port < from.output_port_count() && edge.to_port < to.input_port_count()
+        };
+        if !blocks.is_some_and(valid) {
+            return Err(api::Error::new(format!("arrow #{} -> #{} references an unknown block or port", edge.from, edge.to)));
+        }
+    }
+    Ok(())
+}
+
+impl Group {
+    pub fn validate(&self) -> Result<(), api::Error> {
+        validate_level(self.last_id, &self.blocks, &self.edges)
+    }
+}
+
+impl Project {
+    pub fn validate(&self) -> Result<(), api::Error> {
+        validate_level(self.last_id, &self.blocks, &self.edges)
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{layer::LayerKind, params::{self, Params}, Id};
+
+// tensor shape without the batch dimension
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct Shape(pub Vec<i64>);
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ShapeError {
+    pub message: String,
+    // block whose output doesn't fit, if the mismatch is caused by a single input
+    pub input: Option<Id>,
+}
+
+impl ShapeError {
+    fn new(message: String) -> Self {
+        Self { message, input: None }
+    }
+    fn at_input(message: String, input: Id) -> Self {
+        Self { message, input: Some(input) }
+    }
+}
+
+fn single_input(kind: LayerKind, inputs: &[(Id, Shape)]) -> Result<(Id, &Shape), ShapeError> {
+    match inputs {
+        [(id, shape)] => Ok((*id, shape)),
+        _ => Err(ShapeError::new(format!("{kind} expects exactly one input, got {}", inputs.len()))),
+    }
+}
+
+fn add(inputs: &[(Id, Shape)]) -> Result<Shape, ShapeError> {
+    let [(_, first), rest @ ..] = inputs else {
+        return Err(ShapeError::new("Add expects at least two inputs".to_string()));
+    };
+    if rest.is_empty() {
+        return Err(ShapeError::new("Add expects at least two inputs".to_string()));
+    }
+    match rest.iter().find(|(_, shape)| shape != first) {
+        None => Ok(first.clone()),
+        Some((id, shape)) => Err(ShapeError::at_input(format!("can't add {shape} to {first}"), *id)),
+    }
+}
+
+fn concat(params: &Params, inputs: &[(Id, Shape)]) -> Result<Shape, ShapeError> {
+    let [(_, first), rest @ ..] = inputs else {
+        return Err(ShapeError::new("Concat expects at least two inputs".to_string()));
+    };
+    if rest.is_empty() {
+        return Err(ShapeError::new("Concat expects at least two inputs".to_string()));
+    }
+    // the dimension counts the batch one too
+    let rank = first.0.len() as i64 + 1;
+    let dim = params.int("dim").unwrap_or(1);
+    let dim = if dim < 0 { dim + rank } else { dim };
+    if !(1..rank).contains(&dim) {
+        return Err(ShapeError::new(format!("can't concatenate {first} along dimension {dim}")));
+    }
+    let axis = dim as usize - 1;
+    let mut dims = first.0.clone();
+    for (id, shape) in rest {
+        let fits = shape.0.len() == first.0.len()
+            && shape.0.iter().zip(first.0.iter()).enumerate().all(|(i, (a, b))| i == axis || a == b);
+        if !fits {
+            return Err(ShapeError::at_input(format!("can't concatenate {shape} to {first} along dimension {dim}"), *id));
+        }
+        dims[axis] += shape.0[axis];
+    }
+    Ok(Shape(dims))
+}
+
+// output size of a convolution or pooling window along one dimension, None when the kernel doesn't fit;
+// checked before dividing since the division truncates toward zero
+fn window_output(size: i64, kernel: i64, stride: i64, padding: i64) -> Option<i64> {
+    match size + 2 * padding >= kernel && stride > 0 {
+        true => Some((size + 2 * padding - kernel) / stride + 1),
+        false => None,
+    }
+}
+
+fn windowed(kind: LayerKind, params: &Params, input: Id, shape: &Shape, channels: i64) -> Result<Shape, ShapeError> {
+    let [_, height, width] = shape.0[..] else {
+        return Err(ShapeError::at_input(format!("{kind} expects a [C, H, W] input, got {shape}"), input));
+    };
+    let kernel = params.int("kernel_size").unwrap_or(1);
+    let stride = params.int("stride").unwrap_or(1);
+    let padding = params.int("padding").unwrap_or(0);
+    match (window_output(height, kernel, stride, padding), window_output(width, kernel, stride, padding)) {
+        (Some(height), Some(width)) => Ok(Shape(vec![channels, height, width])),
+        _ => Err(ShapeError::at_input(format!("kernel {kernel} doesn't fit into input {shape}"), input)),
+    }
+}
+
+pub fn infer(kind: LayerKind, params: &Params, inputs: &[(Id, Shape)]) -> Result<Shape, ShapeError> {
+    if kind == LayerKind::Input {
+        return match inputs.is_empty() {
+            true => Ok(Shape(params.dims("shape").unwrap_or_default().to_vec())),
+            false => Err(ShapeError::new("Input block can't have incoming arrows".to_string())),
+        };
+    }
+    match kind {
+        LayerKind::Add => return add(inputs),
+        LayerKind::Concat => return concat(params, inputs),
+        LayerKind::Group => return Err(ShapeError::new("groups are flattened before shape inference".to_string())),
+        LayerKind::Op => {
+            let op = params.text(params::OP_TYPE).unwrap_or_default();
+            return Err(ShapeError::new(format!("{op} is not supported, the shape after it is unknown")));
+        }
+        _ => {}
+    };
+    let (input, shape) = single_input(kind, inputs)?;
+    match kind {
+        LayerKind::Input | LayerKind::Add | LayerKind::Concat | LayerKind::Group | LayerKind::Op => unreachable!(),
+        LayerKind::Linear => {
+            let in_features = params.int("in_features").unwrap_or_default();
+            match shape.0.last() {
+                Some(last) if *last == in_features => {
+                    let mut dims = shape.0.clone();
+                    *dims.last_mut().unwrap() = params.int("out_features").unwrap_or_default();
+                    Ok(Shape(dims))
+                }
+                _ => Err(ShapeError::at_input(format!("expected {in_features} input features, got {shape}"), input)),
+            }
+        }
+        LayerKind::Conv2d => {
+            let in_channels = params.int("in_channels").unwrap_or_default();
+            match shape.0.first() {
+                Some(channels) if *channels == in_channels => {
+                    windowed(kind, params, input, shape, params.int("out_channels").unwrap_or_default())
+                }
+                _ => Err(ShapeError::at_input(format!("expected {in_channels} input channels, got {shape}"), input)),
+            }
+        }
+        LayerKind::MaxPool2d => {
+            let kernel = params.int("kernel_size").unwrap_or(1);
+            let padding = params.int("padding").unwrap_or(0);
+            match 2 * padding <= kernel {
+                true => windowed(kind, params, input, shape, shape.0.first().copied().unwrap_or_default()),
+                false => Err(ShapeError::new(format!("padding {padding} should be at most half of kernel {kernel}"))),
+            }
+        }
+        LayerKind::BatchNorm => {
+            let num_features = params.int("num_features").unwrap_or_default();
+            match shape.0.first() {
+                Some(features) if *features == num_features && shape.0.len() <= 3 => Ok(shape.clone()),
+                _ => Err(ShapeError::at_input(format!("expected [{num_features}, ...] input of rank 1 to 3, got {shape}"), input)),
+            }
+        }
+        LayerKind::Softmax => {
+            // the dimension counts the batch one too
+            let rank = shape.0.len() as i64 + 1;
+            let dim = params.int("dim").unwrap_or(-1);
+            match (-rank..rank).contains(&dim) {
+                true => Ok(shape.clone()),
+                false => Err(ShapeError::at_input(format!("dimension {dim} is out of range for {shape}"), input)),
+            }
+        }
+        LayerKind::Flatten => Ok(Shape(vec![shape.0.iter().product()])),
+        LayerKind::ReLU | LayerKind::LeakyReLU | LayerKind::Dropout | LayerKind::Identity | LayerKind::Output => Ok(shape.clone()),
+        // boundary blocks only exist as identities in a flattened graph
+        LayerKind::GroupInput | LayerKind::GroupOutput => Ok(shape.clone()),
+    }
+}
+
+impl std::fmt::Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", self.0.iter().map(|dim| dim.to_string()).collect::<Vec<_>>().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamValue;
+
+    fn params(kind: LayerKind, values: &[(&str, i64)]) -> Params {
+        let mut params = Params::defaults(kind);
+        values.iter().for_each(|(name, value)| params.set(name, ParamValue::Int(*value)));
+        params
+    }
+
+    #[test]
+    fn window_output_fits() {
+        assert_eq!(window_output(28, 3, 1, 0), Some(26));
+        assert_eq!(window_output(28, 3, 1, 1), Some(28));
+        assert_eq!(window_output(28, 2, 2, 0), Some(14));
+        assert_eq!(window_output(3, 3, 2, 0), Some(1));
+    }
+
+    #[test]
+    fn window_output_rejects_kernel_larger_than_input() {
+        // (2 - 3) / 2 truncates to 0, which would give a size of 1
+        assert_eq!(window_output(2, 3, 2, 0), None);
+        assert_eq!(window_output(1, 3, 1, 0), None);
+        assert_eq!(window_output(1, 3, 1, 1), Some(1));
+    }
+
+    #[test]
+    fn infer_conv() {
+        let params = params(LayerKind::Conv2d, &[("in_channels", 3), ("out_channels", 8), ("kernel_size", 3), ("padding", 1)]);
+        let output = infer(LayerKind::Conv2d, &params, &[(1, Shape(vec![3, 32, 32]))]);
+        assert_eq!(output, Ok(Shape(vec![8, 32, 32])));
+    }
+
+    #[test]
+    fn infer_rejects_small_input() {
+        let params = params(LayerKind::MaxPool2d, &[("kernel_size", 3), ("stride", 2)]);
+        let error = infer(LayerKind::MaxPool2d, &params, &[(1, Shape(vec![4, 2, 2]))]).unwrap_err();
+        assert_eq!(error.input, Some(1));
+    }
+
+    #[test]
+    fn infer_linear_checks_features() {
+        let params = params(LayerKind::Linear, &[("in_features", 10), ("out_features", 4)]);
+        assert_eq!(infer(LayerKind::Linear, &params, &[(1, Shape(vec![10]))]), Ok(Shape(vec![4])));
+        assert_eq!(infer(LayerKind::Linear, &params, &[(1, Shape(vec![9]))]).unwrap_err().input, Some(1));
+    }
+
+    #[test]
+    fn infer_add_and_concat() {
+        let inputs = [(1, Shape(vec![4, 5])), (2, Shape(vec![4, 5]))];
+        assert_eq!(infer(LayerKind::Add, &Params::defaults(LayerKind::Add), &inputs), Ok(Shape(vec![4, 5])));
+        let concat = params(LayerKind::Concat, &[("dim", 2)]);
+        assert_eq!(infer(LayerKind::Concat, &concat, &inputs), Ok(Shape(vec![4, 10])));
+        let mismatch = [(1, Shape(vec![4, 5])), (2, Shape(vec![3, 5]))];
+        assert_eq!(infer(LayerKind::Add, &Params::defaults(LayerKind::Add), &mismatch).unwrap_err().input, Some(2));
+    }
+
+    #[test]
+    fn infer_flatten() {
+        let output = infer(LayerKind::Flatten, &Params::defaults(LayerKind::Flatten), &[(1, Shape(vec![2, 3, 4]))]);
+        assert_eq!(output, Ok(Shape(vec![24])));
+    }
+}
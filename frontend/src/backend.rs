@@ -0,0 +1,122 @@
+use common::api;
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    // exposed by tauri because of `app.with-global-tauri`
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn tauri_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+}
+
+async fn invoke<Args: Serialize, T: DeserializeOwned>(cmd: &str, args: &Args) -> Result<T, api::Error> {
+    // tauri expects plain objects, not js maps
+    let args = args
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(api::Error::new)?;
+    match tauri_invoke(cmd, args).await {
+        Ok(value) => serde_wasm_bindgen::from_value(value).map_err(api::Error::new),
+        Err(error) => Err(serde_wasm_bindgen::from_value(error.clone())
+            .unwrap_or_else(|_| api::Error::new(format!("command {cmd} failed: {error:?}")))),
+    }
+}
+
+#[derive(Serialize)]
+struct GraphArgs<'a> {
+    graph: &'a common::graph::Graph,
+}
+
+pub async fn compile(graph: common::graph::Graph) -> Result<api::CompileReport, api::Error> {
+    invoke("compile", &GraphArgs { graph: &graph }).await
+}
+
+#[derive(Serialize)]
+struct RunArgs<'a> {
+    graph: &'a common::graph::Graph,
+    input: &'a api::SampleInput,
+}
+
+pub async fn run_forward(graph: common::graph::Graph, input: api::SampleInput) -> Result<api::ForwardReport, api::Error> {
+    invoke("run_forward", &RunArgs { graph: &graph, input: &input }).await
+}
+
+#[derive(Serialize)]
+struct ExportArgs<'a> {
+    graph: &'a common::graph::Graph,
+    path: &'a str,
+}
+
+// the format follows the file extension
+pub async fn export_model(graph: common::graph::Graph, path: String) -> Result<(), api::Error> {
+    invoke("export_model", &ExportArgs { graph: &graph, path: &path }).await
+}
+
+#[derive(Serialize)]
+struct SaveScriptArgs<'a> {
+    graph: &'a common::graph::Graph,
+    path: &'a str,
+    weights: Option<&'a str>,
+}
+
+// without a weights file the model keeps its initialized weights
+pub async fn save_torchscript(graph: common::graph::Graph, path: String, weights: Option<String>) -> Result<(), api::Error> {
+    invoke("save_torchscript", &SaveScriptArgs { graph: &graph, path: &path, weights: weights.as_deref() }).await
+}
+
+#[derive(Serialize)]
+struct RunScriptArgs<'a> {
+    path: &'a str,
+    graph: &'a common::graph::Graph,
+    input: &'a api::SampleInput,
+}
+
+pub async fn run_torchscript(path: String, graph: common::graph::Graph, input: api::SampleInput) -> Result<api::InferenceReport, api::Error> {
+    invoke("run_torchscript", &RunScriptArgs { path: &path, graph: &graph, input: &input }).await
+}
+
+#[derive(Serialize)]
+struct SaveProjectArgs<'a> {
+    path: &'a str,
+    project: &'a common::project::Project,
+}
+
+pub async fn save_project(path: String, project: common::project::Project) -> Result<(), api::Error> {
+    invoke("save_project", &SaveProjectArgs { path: &path, project: &project }).await
+}
+
+#[derive(Serialize)]
+struct PathArgs<'a> {
+    path: &'a str,
+}
+
+pub async fn load_project(path: String) -> Result<api::LoadedProject, api::Error> {
+    invoke("load_project", &PathArgs { path: &path }).await
+}
+
+// the model comes as a new project with the blocks laid out
+pub async fn import_model(path: String) -> Result<common::project::Project, api::Error> {
+    invoke("import_model", &PathArgs { path: &path }).await
+}
+
+pub async fn list_components() -> Result<Vec<common::library::ComponentRef>, api::Error> {
+    invoke("list_components", &()).await
+}
+
+#[derive(Serialize)]
+struct ComponentArgs<'a> {
+    name: &'a str,
+}
+
+pub async fn load_component(name: String) -> Result<common::library::Component, api::Error> {
+    invoke("load_component", &ComponentArgs { name: &name }).await
+}
+
+#[derive(Serialize)]
+struct SaveComponentArgs<'a> {
+    name: &'a str,
+    group: &'a common::project::Group,
+}
+
+pub async fn save_component(name: String, group: common::project::Group) -> Result<common::library::ComponentRef, api::Error> {
+    invoke("save_component", &SaveComponentArgs { name: &name, group: &group }).await
+}
@@ -0,0 +1,27 @@
+use wasm_bindgen_futures::JsFuture;
+
+fn clipboard() -> Option<web_sys::Clipboard> {
+    Some(web_sys::window()?.navigator().clipboard())
+}
+
+// the write finishes in the background, a failure only loses the system copy
+pub fn write_text(text: String) {
+    let Some(clipboard) = clipboard() else {
+        return;
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(error) = JsFuture::from(clipboard.write_text(&text)).await {
+            log::warn!("cannot write to the clipboard: {error:?}");
+        }
+    });
+}
+
+pub async fn read_text() -> Option<String> {
+    match JsFuture::from(clipboard()?.read_text()).await {
+        Ok(text) => text.as_string(),
+        Err(error) => {
+            log::warn!("cannot read the clipboard: {error:?}");
+            None
+        }
+    }
+}
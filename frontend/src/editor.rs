@@ -1,29 +1,170 @@
+mod arrange_menu;
+mod block_menu;
 mod board;
+mod breadcrumb;
 mod cursor;
 mod event;
+mod inspector;
+mod library;
+mod minimap;
+mod problems;
+mod status;
+mod toolbar;
 mod viewbox;
 
 use event::Event;
 use glam::DVec2;
+use wasm_bindgen::{closure::Closure, JsCast};
 use yew::prelude::*;
 use crate::tools::viewable::Viewable;
 
 #[derive(PartialEq, Properties)]
-pub struct Props;
+pub struct Props {
+    // number of edits that can be undone
+    #[prop_or(board::state::history::DEFAULT_DEPTH)]
+    pub history_depth: usize,
+    #[prop_or(viewbox::MIN_SCALE)]
+    pub min_zoom: f64,
+    #[prop_or(viewbox::MAX_SCALE)]
+    pub max_zoom: f64,
+    // the wheel zooms over a few frames instead of at once
+    #[prop_or(true)]
+    pub smooth_zoom: bool,
+}
 
 #[derive(Default)]
 pub struct Editor {
     viewbox: viewbox::State,
     board: board::State,
     cursor: cursor::Cursor,
+    block_menu: Option<block_menu::State>,
+    // a smooth zoom in progress
+    zoom: Option<viewbox::zoom::Zoom>,
+    // where the arrange menu was opened, in app coordinates
+    arrange_menu: Option<DVec2>,
+    status: status::Status,
+    sample_input: String,
+    // dragged and new blocks go to the grid
+    snap_to_grid: bool,
+    minimap_hidden: bool,
+    // last path a project was saved to or loaded from
+    project_path: String,
+    // last path the model was exported to
+    export_path: String,
+    // last path a model was imported from
+    import_path: String,
+    // last TorchScript file saved or run
+    script_path: String,
+    // used when the system clipboard has no blocks
+    clipboard: common::project::Fragment,
+    svg: NodeRef,
+    // tells about every change of the board element size, kept so it can be stopped
+    resize_observer: Option<(web_sys::ResizeObserver, Closure<dyn FnMut()>)>,
+    components: Vec<common::library::ComponentRef>,
+}
+
+impl Editor {
+    // replaces the board and the view with the ones of a loaded or imported project
+    fn open_project(&mut self, ctx: &Context<Self>, project: &common::project::Project) {
+        self.board.set_new_state(board::State::from_project(project));
+        self.board.internal_mut().set_history_depth(ctx.props().history_depth);
+        self.viewbox.set_new_state(viewbox::State::from_project(&project.viewbox, (ctx.props().min_zoom, ctx.props().max_zoom)));
+        self.measure();
+    }
+
+    // where the board element is in the window, None before it is mounted
+    fn screen(&self) -> Option<viewbox::Screen> {
+        let rect = self.svg.cast::<web_sys::Element>()?.get_bounding_client_rect();
+        Some(viewbox::Screen {
+            pos: DVec2::new(rect.left(), rect.top()),
+            size: DVec2::new(rect.width(), rect.height()),
+        })
+    }
+
+    // the board coordinates follow the element, wherever the layout puts it; false when it stayed in place
+    fn measure(&mut self) -> bool {
+        match self.screen() {
+            Some(screen) if self.viewbox.get_screen() != Some(screen) => {
+                self.viewbox.set_screen(screen);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn observe_resize(&mut self, ctx: &Context<Self>) {
+        let Some(svg) = self.svg.cast::<web_sys::Element>() else {
+            return;
+        };
+        let link = ctx.link().clone();
+        let callback = Closure::<dyn FnMut()>::new(move || link.send_message(Event::Resized));
+        match web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()) {
+            Ok(observer) => {
+                observer.observe(&svg);
+                self.resize_observer = Some((observer, callback));
+            }
+            Err(error) => log::warn!("cannot watch the board size: {error:?}"),
+        }
+    }
+
+    // the left button went down with space held
+    fn start_pan(&mut self) -> bool {
+        let viewbox::State::Ready(s) = &self.viewbox else {
+            return false;
+        };
+        let new_s = s.clone().drag().to_states_enum();
+        self.viewbox.set_new_state(new_s);
+        true
+    }
+
+    // shows all blocks or the selected ones, false when there are none
+    fn zoom_to_blocks(&mut self, ctx: &Context<Self>, selected_only: bool) -> bool {
+        let Some((top_left, bottom_right)) = self.board.internal().bounds(selected_only) else {
+            return false;
+        };
+        self.zoom = None;
+        self.viewbox.fit(top_left, bottom_right, (ctx.props().min_zoom, ctx.props().max_zoom));
+        true
+    }
+
+    // the blocks move over the next frames, each one scheduling the one after it
+    fn layout(&mut self, ctx: &Context<Self>, selected_only: bool) -> bool {
+        let board::State::Basic(s) = &mut self.board else {
+            return false;
+        };
+        if !s.layout(selected_only) {
+            return false;
+        }
+        ctx.link().send_message(Event::LayoutFrame);
+        true
+    }
 }
 
 impl Component for Editor {
     type Message = Event;
     type Properties = Props;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        return Self::default()
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut editor = Self::default();
+        editor.board.internal_mut().set_history_depth(ctx.props().history_depth);
+        ctx.link().send_future(async { Event::ComponentsListed(crate::backend::list_components().await) });
+        editor
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.observe_resize(ctx);
+        }
+        // the observer only tells about size changes, while panels shown or hidden around the board move it too
+        if self.screen() != self.viewbox.get_screen() {
+            ctx.link().send_message(Event::Resized);
+        }
+    }
+
+    fn destroy(&mut self, _: &Context<Self>) {
+        if let Some((observer, _)) = self.resize_observer.take() {
+            observer.disconnect();
+        }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -36,19 +177,44 @@ impl Component for Editor {
             }
         });
         let onkeydown = ctx.link().callback(Event::KeyDown);
+        let onkeyup = ctx.link().callback(Event::KeyUp);
         let onmouseup = ctx.link().callback(Event::MouseUp);
         let onmousedown = ctx.link().callback(Event::MouseDown);
         let onwheel = ctx.link().callback(Event::MouseWheel);
+        // the board accepts drops only when the default is prevented
+        let ondragover = |e: DragEvent| e.prevent_default();
+        let ondrop = ctx.link().callback(Event::Drop);
+        let oncontextmenu = ctx.link().callback(Event::ContextMenu);
+        let (grid_top_left, grid_size) = self.viewbox.visible_area();
 
         html! {
-            <div tabindex="0" {onkeydown} {onmousemove} {onmousedown} {onmouseup} {onwheel}>
+            <div tabindex="0" style={self.viewbox.cursor_style()} {onkeydown} {onkeyup} {onmousemove} {onmousedown} {onmouseup} {onwheel} {ondragover} {ondrop} {oncontextmenu}>
                 <svg
+                ref={self.svg.clone()}
                 width = "100%"
                 height = "100%"
                 viewBox={self.viewbox.make_viewbox_str()}
                 xmlns="http://www.w3.org/2000/svg">
+                    {board::State::grid_html(grid_top_left, grid_size)}
                     {self.board.view(ctx.link().callback(Event::BoardEvent))}
                 </svg>
+                {self.board.internal().single_selected().map(|block| html! {
+                    <inspector::Inspector {block} scope={ctx.link().callback(Event::Inspector)} />
+                })}
+                <toolbar::Toolbar busy={self.status.is_running()} sample_input={self.sample_input.clone()} snap={self.snap_to_grid} minimap={!self.minimap_hidden} scope={ctx.link().callback(Event::Toolbar)} />
+                <problems::Problems problems={self.board.internal().problems().to_vec()} scope={ctx.link().callback(Event::Problems)} />
+                <breadcrumb::Breadcrumb path={self.board.internal().path()} scope={ctx.link().callback(Event::Breadcrumb)} />
+                <library::Library components={self.components.clone()} scope={ctx.link().callback(Event::Library)} />
+                {(!self.minimap_hidden).then(|| html! {
+                    <minimap::Minimap overview={minimap::Overview::from(self.board.internal())} view={self.viewbox.visible_area()} scope={ctx.link().callback(Event::Minimap)} />
+                })}
+                {self.status.html()}
+                {self.block_menu.map(|state| html! {
+                    <block_menu::BlockMenu {state} in_group={!self.board.internal().path().is_empty()} scope={ctx.link().callback(Event::BlockMenu)} />
+                })}
+                {self.arrange_menu.map(|pos| html! {
+                    <arrange_menu::ArrangeMenu {pos} selected={self.board.internal().selected_count()} scope={ctx.link().callback(Event::ArrangeMenu)} />
+                })}
             </div>
         }
     }
@@ -63,7 +229,7 @@ impl Component for Editor {
                         s.move_box(-delta);
                         true
                     }
-                    viewbox::State::Basic(_) => {
+                    viewbox::State::Basic(_) | viewbox::State::Ready(_) => {
                         match &mut self.board {
                             board::State::PredragBlocks(s) => {
                                 let new_s = s.clone().drag_blocks().to_states_enum();
@@ -72,7 +238,7 @@ impl Component for Editor {
                                 false
                             }
                             board::State::DraggingBlocks(s) => {
-                                let new_s = s.clone().move_selected(delta).to_states_enum();
+                                let new_s = s.clone().move_selected(delta, self.snap_to_grid).to_states_enum();
                                 self.board.set_new_state(new_s);
                                 true
                             }
@@ -86,6 +252,15 @@ impl Component for Editor {
                     }
                 }
             }
+            Event::MouseUp(e) if e.button() == 0 && matches!(self.viewbox, viewbox::State::Dragged(_)) => {
+                // a drag with space held
+                let viewbox::State::Dragged(s) = &self.viewbox else {
+                    return false;
+                };
+                let new_s = s.clone().drop();
+                self.viewbox.set_new_state(new_s);
+                true
+            }
             Event::MouseUp(e) => match e.button() {
                 0 => {
                     // left button click
@@ -122,12 +297,12 @@ impl Component for Editor {
                 1 => {
                     // middle button click
                     match &mut self.viewbox {
-                        viewbox::State::Basic(_) => {
-                            log::warn!("basic state on middle click mouse release");
+                        viewbox::State::Basic(_) | viewbox::State::Ready(_) => {
+                            log::warn!("{} state on middle click mouse release", self.viewbox);
                             false
                         }
                         viewbox::State::Dragged(s) => {
-                            let new_s = s.clone().drop().to_states_enum();
+                            let new_s = s.clone().drop();
                             self.viewbox.set_new_state(new_s);
                             false
                         }
@@ -135,6 +310,12 @@ impl Component for Editor {
                 }
                 _ => false,
             }
+            Event::MouseDown(_) if self.block_menu.is_some() || self.arrange_menu.is_some() => {
+                self.block_menu = None;
+                self.arrange_menu = None;
+                true
+            }
+            Event::MouseDown(e) if e.button() == 0 && matches!(self.viewbox, viewbox::State::Ready(_)) => self.start_pan(),
             Event::MouseDown(e) => match e.button() {
                 0 => {
                     // left button click
@@ -159,18 +340,24 @@ impl Component for Editor {
                             let new_s = s.clone().drag().to_states_enum();
                             self.viewbox.set_new_state(new_s);
                         }
+                        viewbox::State::Ready(_) => { self.start_pan(); }
                         viewbox::State::Dragged(_) => { log::warn!("dragged state on middle click mouse hold"); }
                     };
                     false
                 }
                 _ => false,
             }
+            // with space held the board is only grabbed
+            Event::BoardEvent(
+                board::Event::BlockEvent(board::block::Event::MouseDown(..) | board::block::Event::PortMouseDown(..))
+                | board::Event::ArrowEvent(board::arrow::Event::MouseDown(..))
+            ) if matches!(self.viewbox, viewbox::State::Ready(_)) => self.start_pan(),
             Event::BoardEvent(board::Event::BlockEvent(board::block::Event::MouseDown(e, id))) => match e.button() {
                 // left button click
                 0 => match &mut self.board {
                     board::State::ArrowCreation(stages) => match stages {
                         board::state::arrow_creation::StateStages::Start(s) => {
-                            let new_s = s.clone().finish(id).to_states_enum();
+                            let new_s = s.clone().finish(id, None).to_states_enum();
                             self.board.set_new_state(new_s);
                             true
                         }
@@ -196,17 +383,100 @@ impl Component for Editor {
                             let new_s = s.clone().drag().to_states_enum();
                             self.viewbox.set_new_state(new_s);
                         }
+                        viewbox::State::Ready(_) => { self.start_pan(); }
                         viewbox::State::Dragged(_) => { log::warn!("dragged state on middle click mouse hold"); }
                     };
                     false
                 }
                 _ => false,
             }
+            // left button click, arrows go from an output port to an input one
+            Event::BoardEvent(board::Event::BlockEvent(board::block::Event::PortMouseDown(id, port))) => match (port, &mut self.board) {
+                (board::block::Port::Output(port), board::State::Basic(s)) => {
+                    let new_s = s.clone().create_arrow_from_port(id, port).to_states_enum();
+                    self.board.set_new_state(new_s);
+                    true
+                }
+                (board::block::Port::Input(port), board::State::ArrowCreation(board::state::arrow_creation::StateStages::Start(s))) => {
+                    let new_s = s.clone().finish(id, Some(port)).to_states_enum();
+                    self.board.set_new_state(new_s);
+                    true
+                }
+                _ => false,
+            }
+            Event::BoardEvent(board::Event::BlockEvent(board::block::Event::DoubleClick(id))) => match &mut self.board {
+                board::State::Basic(s) => s.enter_group(id),
+                _ => false,
+            }
+            Event::BoardEvent(board::Event::ArrowEvent(board::arrow::Event::MouseDown(e, edge))) => match &mut self.board {
+                board::State::Basic(s) => {
+                    s.click_arrow(edge, match e.ctrl_key() {
+                        false => board::state::predrag::SelectionModifier::None,
+                        true => board::state::predrag::SelectionModifier::Add,
+                    });
+                    true
+                }
+                _ => false,
+            }
+            Event::KeyDown(event) if self.block_menu.is_some() || self.arrange_menu.is_some() => match event.key().as_str() {
+                "Escape" => {
+                    self.block_menu = None;
+                    self.arrange_menu = None;
+                    true
+                }
+                _ => false,
+            }
+            Event::KeyDown(event) if event.key() == " " => {
+                // the page must not scroll
+                event.prevent_default();
+                let viewbox::State::Basic(s) = &self.viewbox else {
+                    return false;
+                };
+                let new_s = s.clone().hold_space().to_states_enum();
+                self.viewbox.set_new_state(new_s);
+                true
+            }
+            Event::KeyUp(event) => match (event.key().as_str(), &mut self.viewbox) {
+                (" ", viewbox::State::Ready(s)) => {
+                    let new_s = s.clone().release_space().to_states_enum();
+                    self.viewbox.set_new_state(new_s);
+                    true
+                }
+                // the drag goes on until the button is released
+                (" ", viewbox::State::Dragged(s)) => {
+                    s.release_space();
+                    false
+                }
+                _ => false,
+            },
+            Event::KeyDown(event) if event.ctrl_key() && matches!(event.key().as_str(), "s" | "o") => {
+                // keep the webview from handling the shortcut itself
+                event.prevent_default();
+                let save = event.key() == "s";
+                let Some(path) = prompt(if save { "Save project to" } else { "Open project" }, &self.project_path) else {
+                    return false;
+                };
+                self.project_path = path.clone();
+                if save {
+                    let project = self.board.internal().to_project(self.viewbox.to_project());
+                    ctx.link().send_future(async move { Event::ProjectSaved(crate::backend::save_project(path, project).await) });
+                    self.status = status::Status::Running("Saving");
+                } else {
+                    ctx.link().send_future(async move { Event::ProjectLoaded(crate::backend::load_project(path).await) });
+                    self.status = status::Status::Running("Loading");
+                }
+                true
+            }
             Event::KeyDown(event) => {
                 match &mut self.board {
                 board::state::State::Basic(s) => {
+                    if let Some(arrangement) = board::state::arrangement::Arrangement::from_shortcut(&event) {
+                        event.prevent_default();
+                        return s.arrange_selected(arrangement);
+                    }
                     match event.key().as_str() {
-                    "a" => {
+                    // plain "a" pans left with the other WASD keys
+                    "A" if !event.ctrl_key() => {
                         let result = s.clone().try_create_arrow();
                         match result {
                             Ok(state) => {
@@ -219,12 +489,67 @@ impl Component for Editor {
                             }
                         }
                     }
+                    key if !event.ctrl_key() && pan_direction(key).is_some() => {
+                        event.prevent_default();
+                        self.viewbox.pan(pan_direction(key).unwrap() * viewbox::PAN_STEP);
+                        true
+                    }
                     "n" => {
-                        s.create_block(self.viewbox.to_board_coords(self.cursor.get()));
+                        self.block_menu = Some(block_menu::State {
+                            app_pos: self.cursor.get(),
+                            board_pos: self.viewbox.to_board_coords(self.cursor.get()),
+                        });
+                        true
+                    }
+                    "c" | "x" if event.ctrl_key() => {
+                        // an empty copy would wipe what the clipboard holds
+                        if s.internal().selected_count() == 0 {
+                            return false;
+                        }
+                        let cut = event.key() == "x";
+                        self.clipboard = match cut {
+                            true => s.cut_selected(),
+                            false => s.copy_selected(),
+                        };
+                        match serde_json::to_string(&self.clipboard) {
+                            Ok(json) => crate::clipboard::write_text(json),
+                            Err(error) => log::warn!("cannot serialize the copied blocks: {error}"),
+                        };
+                        cut
+                    }
+                    "v" if event.ctrl_key() => {
+                        ctx.link().send_future(async { Event::Paste(crate::clipboard::read_text().await) });
+                        false
+                    }
+                    "g" | "G" if event.ctrl_key() => {
+                        event.prevent_default();
+                        let result = match event.shift_key() {
+                            false => s.group_selected(),
+                            true => s.ungroup_selected(),
+                        };
+                        if let Err(message) = result {
+                            self.status = status::Status::Failed(common::api::Error::new(message));
+                        }
+                        true
+                    }
+                    "z" | "Z" if event.ctrl_key() => match event.shift_key() {
+                        false => s.undo(),
+                        true => s.redo(),
+                    },
+                    "f" | "F" if !event.ctrl_key() => {
+                        let selected_only = event.shift_key();
+                        self.zoom_to_blocks(ctx, selected_only)
+                    }
+                    "m" if !event.ctrl_key() => {
+                        self.minimap_hidden = !self.minimap_hidden;
                         true
                     }
+                    "l" | "L" if !event.ctrl_key() => {
+                        let selected_only = event.shift_key();
+                        self.layout(ctx, selected_only)
+                    }
                     "Delete" => {
-                        s.remove_selected_blocks();
+                        s.remove_selected();
                         true
                     },
                     "Escape" => {
@@ -252,7 +577,381 @@ impl Component for Editor {
                 },
                 _ => false,
             }},
-            Event::MouseWheel(event) => {self.viewbox.scale(self.cursor.get(), event.delta_y()); true}
+            Event::BlockMenu(block_menu::Event::Select(kind)) => {
+                match (self.block_menu.take(), &mut self.board) {
+                    (Some(menu), board::State::Basic(s)) => {
+                        s.create_block(menu.board_pos, kind, self.snap_to_grid);
+                    }
+                    _ => {
+                        log::warn!("block menu selection on state {}", self.board);
+                    }
+                };
+                true
+            }
+            Event::ContextMenu(event) => {
+                // the webview menu has nothing for the board
+                event.prevent_default();
+                let board::State::Basic(_) = &self.board else {
+                    return false;
+                };
+                self.arrange_menu = Some(self.cursor.get());
+                true
+            }
+            Event::ArrangeMenu(arrange_menu::Event::Select(arrangement)) => match (self.arrange_menu.take(), &mut self.board) {
+                (Some(_), board::State::Basic(s)) => {
+                    s.arrange_selected(arrangement);
+                    true
+                }
+                _ => false,
+            },
+            Event::Inspector(inspector::Event::SetParam { block, name, value }) => match &mut self.board {
+                board::State::Basic(s) => {
+                    s.set_param(block, name, value);
+                    true
+                }
+                _ => {
+                    log::warn!("parameter change on state {}", self.board);
+                    false
+                }
+            }
+            Event::Paste(text) => {
+                // text that is not a copied fragment falls back to the last copy from this window
+                let fragment = text
+                    .and_then(|text| serde_json::from_str::<common::project::Fragment>(&text).ok())
+                    .unwrap_or_else(|| self.clipboard.clone());
+                match &mut self.board {
+                    board::State::Basic(s) => {
+                        s.paste(&fragment, self.viewbox.to_board_coords(self.cursor.get()));
+                        true
+                    }
+                    _ => {
+                        log::warn!("paste on state {}", self.board);
+                        false
+                    }
+                }
+            }
+            Event::Problems(problems::Event::Select(block)) => match &mut self.board {
+                board::State::Basic(s) => {
+                    s.select_block(block);
+                    true
+                }
+                _ => false,
+            }
+            Event::Breadcrumb(breadcrumb::Event::Exit(levels)) => match &mut self.board {
+                board::State::Basic(s) => {
+                    s.exit_group(levels);
+                    true
+                }
+                _ => false,
+            }
+            Event::Drop(event) => {
+                event.prevent_default();
+                let name = event.data_transfer()
+                    .and_then(|data| data.get_data(library::DRAG_FORMAT).ok())
+                    .filter(|name| !name.is_empty());
+                let Some(name) = name else {
+                    return false;
+                };
+                let pos = self.viewbox.to_board_coords(DVec2 { x: event.client_x() as f64, y: event.client_y() as f64 });
+                ctx.link().send_future(async move { Event::ComponentLoaded(crate::backend::load_component(name).await, pos) });
+                false
+            }
+            Event::ComponentLoaded(result, pos) => match (result, &mut self.board) {
+                (Ok(component), board::State::Basic(s)) => {
+                    s.add_component(&component, pos);
+                    true
+                }
+                (Ok(_), _) => {
+                    log::warn!("component dropped on state {}", self.board);
+                    false
+                }
+                (Err(error), _) => {
+                    self.status = status::Status::Failed(error);
+                    true
+                }
+            }
+            Event::Library(library::Event::SaveSelected) => {
+                let Some((block, component, group)) = self.board.internal().selected_group() else {
+                    self.status = status::Status::Failed(common::api::Error::new("select a group block to save it to the library"));
+                    return true;
+                };
+                // saving an instance under its own name makes a new version
+                let default = component.map(|component| component.name).unwrap_or_default();
+                let Some(name) = prompt("Save component as", &default) else {
+                    return false;
+                };
+                ctx.link().send_future(async move { Event::ComponentSaved(block, crate::backend::save_component(name, group).await) });
+                self.status = status::Status::Running("Saving component");
+                true
+            }
+            Event::Library(library::Event::Refresh) => {
+                ctx.link().send_future(async { Event::ComponentsListed(crate::backend::list_components().await) });
+                false
+            }
+            Event::ComponentsListed(result) => match result {
+                Ok(components) => {
+                    self.components = components;
+                    true
+                }
+                Err(error) => {
+                    log::warn!("cannot list library components: {}", error.message);
+                    false
+                }
+            }
+            Event::ComponentSaved(block, result) => {
+                self.status = match result {
+                    Ok(component) => {
+                        self.board.internal_mut().set_component(block, component);
+                        ctx.link().send_future(async { Event::ComponentsListed(crate::backend::list_components().await) });
+                        status::Status::Done("Component saved")
+                    }
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::Toolbar(toolbar::Event::Build) => {
+                let graph = self.board.internal().export_graph();
+                ctx.link().send_future(async move { Event::BuildFinished(crate::backend::compile(graph).await) });
+                self.status = status::Status::Running("Building");
+                true
+            }
+            Event::Toolbar(toolbar::Event::Run) => {
+                let input = match parse_sample_input(&self.sample_input) {
+                    Ok(input) => input,
+                    Err(error) => {
+                        self.status = status::Status::Failed(error);
+                        return true;
+                    }
+                };
+                let graph = self.board.internal().export_graph();
+                ctx.link().send_future(async move { Event::RunFinished(crate::backend::run_forward(graph, input).await) });
+                self.status = status::Status::Running("Running");
+                true
+            }
+            Event::Toolbar(toolbar::Event::Export) => {
+                let default = match self.export_path.is_empty() {
+                    true => "model.py",
+                    false => &self.export_path,
+                };
+                let Some(path) = prompt("Export model to (.py, .onnx)", default) else {
+                    return false;
+                };
+                self.export_path = path.clone();
+                let graph = self.board.internal().export_graph();
+                ctx.link().send_future(async move { Event::Exported(crate::backend::export_model(graph, path).await) });
+                self.status = status::Status::Running("Exporting");
+                true
+            }
+            Event::Toolbar(toolbar::Event::Import) => {
+                let Some(path) = prompt("Import model from (.onnx)", &self.import_path) else {
+                    return false;
+                };
+                self.import_path = path.clone();
+                ctx.link().send_future(async move { Event::Imported(crate::backend::import_model(path).await) });
+                self.status = status::Status::Running("Importing");
+                true
+            }
+            Event::Toolbar(toolbar::Event::SaveScript) => {
+                let default = match self.script_path.is_empty() {
+                    true => "model.pt",
+                    false => &self.script_path,
+                };
+                let Some(path) = prompt("Save TorchScript model to", default) else {
+                    return false;
+                };
+                self.script_path = path.clone();
+                // cancelled or left empty keeps the initialized weights, every save writes its weights next to the model
+                let weights = prompt("Load weights saved with an earlier model, like model.ot (empty for initialized weights)", "");
+                let graph = self.board.internal().export_graph();
+                ctx.link().send_future(async move { Event::ScriptSaved(crate::backend::save_torchscript(graph, path, weights).await) });
+                self.status = status::Status::Running("Saving TorchScript");
+                true
+            }
+            Event::Toolbar(toolbar::Event::RunScript) => {
+                let input = match parse_sample_input(&self.sample_input) {
+                    Ok(input) => input,
+                    Err(error) => {
+                        self.status = status::Status::Failed(error);
+                        return true;
+                    }
+                };
+                let Some(path) = prompt("Run TorchScript model from", &self.script_path) else {
+                    return false;
+                };
+                self.script_path = path.clone();
+                let graph = self.board.internal().export_graph();
+                ctx.link().send_future(async move { Event::ScriptRun(crate::backend::run_torchscript(path, graph, input).await) });
+                self.status = status::Status::Running("Running TorchScript");
+                true
+            }
+            Event::Toolbar(toolbar::Event::Layout) => self.layout(ctx, false),
+            Event::LayoutFrame => {
+                let moved = self.board.internal_mut().animate();
+                if moved {
+                    ctx.link().send_future(async {
+                        yew::platform::time::sleep(board::state::animation::FRAME_TIME).await;
+                        Event::LayoutFrame
+                    });
+                }
+                moved
+            }
+            Event::Toolbar(toolbar::Event::SetMinimap(shown)) => {
+                self.minimap_hidden = !shown;
+                true
+            }
+            Event::Minimap(minimap::Event::MoveTo(center)) => {
+                self.zoom = None;
+                self.viewbox.center_on(center);
+                true
+            }
+            Event::Toolbar(toolbar::Event::SetSnap(snap)) => {
+                self.snap_to_grid = snap;
+                true
+            }
+            Event::Toolbar(toolbar::Event::SetSampleInput(value)) => {
+                self.sample_input = value;
+                true
+            }
+            Event::RunFinished(result) => {
+                self.status = match result {
+                    Ok(report) => {
+                        self.board.internal_mut().set_run_results(report);
+                        status::Status::Done("Forward pass done")
+                    }
+                    Err(error) => {
+                        self.board.internal_mut().set_run_error(&error);
+                        status::Status::Failed(error)
+                    }
+                };
+                true
+            }
+            Event::ProjectSaved(result) => {
+                self.status = match result {
+                    Ok(()) => status::Status::Done("Project saved"),
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::Exported(result) => {
+                self.status = match result {
+                    Ok(()) => status::Status::Done("Model exported"),
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::ScriptSaved(result) => {
+                self.status = match result {
+                    Ok(()) => status::Status::Done("TorchScript model saved"),
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::ScriptRun(result) => {
+                self.status = match result {
+                    Ok(report) => status::Status::Inferred(report),
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::ProjectLoaded(result) => {
+                self.status = match result {
+                    Ok(loaded) => {
+                        self.open_project(ctx, &loaded.project);
+                        match loaded.update.is_empty() {
+                            true => status::Status::Done("Project loaded"),
+                            false => status::Status::Updated(loaded.update),
+                        }
+                    }
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::Imported(result) => {
+                self.status = match result {
+                    Ok(project) => {
+                        self.open_project(ctx, &project);
+                        status::Status::Done("Model imported")
+                    }
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            Event::BuildFinished(result) => {
+                self.status = match result {
+                    Ok(report) => status::Status::Built(report),
+                    Err(error) => status::Status::Failed(error),
+                };
+                true
+            }
+            // trackpads pinch as a wheel with ctrl held
+            Event::MouseWheel(event) if !event.ctrl_key() => {
+                event.prevent_default();
+                self.viewbox.pan(viewbox::wheel_pixels(&event));
+                true
+            }
+            Event::MouseWheel(event) => {
+                // keep the webview from zooming the page on a pinch
+                event.prevent_default();
+                let factor = viewbox::zoom::wheel_factor(&event);
+                let limits = (ctx.props().min_zoom, ctx.props().max_zoom);
+                if !ctx.props().smooth_zoom {
+                    self.viewbox.scale(self.cursor.get(), factor, limits);
+                    return true;
+                }
+                let scale = self.viewbox.get_scale();
+                match &mut self.zoom {
+                    Some(zoom) => zoom.add(self.cursor.get(), factor, scale, limits),
+                    None => {
+                        self.zoom = Some(viewbox::zoom::Zoom::new(self.cursor.get(), factor, scale, limits));
+                        ctx.link().send_message(Event::ZoomFrame);
+                    }
+                }
+                false
+            }
+            Event::Resized => self.measure(),
+            Event::ZoomFrame => {
+                let Some((cursor, factor)) = self.zoom.as_mut().and_then(viewbox::zoom::Zoom::next) else {
+                    self.zoom = None;
+                    return false;
+                };
+                self.viewbox.scale(cursor, factor, (ctx.props().min_zoom, ctx.props().max_zoom));
+                ctx.link().send_future(async {
+                    yew::platform::time::sleep(viewbox::zoom::FRAME_TIME).await;
+                    Event::ZoomFrame
+                });
+                true
+            }
         }
     }
 }
+
+// arrow keys and WASD, arrows are started with Shift+A
+fn pan_direction(key: &str) -> Option<DVec2> {
+    match key {
+        "ArrowLeft" | "a" => Some(DVec2::NEG_X),
+        "ArrowRight" | "d" => Some(DVec2::X),
+        "ArrowUp" | "w" => Some(DVec2::NEG_Y),
+        "ArrowDown" | "s" => Some(DVec2::Y),
+        _ => None,
+    }
+}
+
+// empty input means a random sample
+fn parse_sample_input(raw: &str) -> Result<common::api::SampleInput, common::api::Error> {
+    if raw.trim().is_empty() {
+        return Ok(common::api::SampleInput::Random { batch: 1 });
+    }
+    raw.split(',')
+        .map(|value| value.trim().parse::<f64>().map_err(|_| common::api::Error::new(format!("invalid sample value \"{}\"", value.trim()))))
+        .collect::<Result<Vec<_>, _>>()
+        .map(common::api::SampleInput::Values)
+}
+
+// asks for a line of text, None when cancelled or left empty
+fn prompt(message: &str, default: &str) -> Option<String> {
+    web_sys::window()?
+        .prompt_with_message_and_default(message, default)
+        .ok()
+        .flatten()
+        .filter(|path| !path.trim().is_empty())
+}
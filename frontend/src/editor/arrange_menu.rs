@@ -0,0 +1,75 @@
+use glam::DVec2;
+use yew::{html, Callback, Html, MouseEvent};
+
+use super::board::state::arrangement::Arrangement;
+
+pub enum Event {
+    Select(Arrangement),
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    // where the board was right clicked, in app coordinates
+    pub pos: DVec2,
+    // number of selected blocks, commands needing more are disabled
+    pub selected: usize,
+    pub scope: Callback<Event>,
+}
+
+#[derive(Default)]
+pub struct ArrangeMenu;
+
+impl ArrangeMenu {
+    fn item_html(arrangement: Arrangement, props: &Props) -> Html {
+        let enabled = props.selected >= arrangement.min_blocks();
+        let scope = props.scope.clone();
+        let onclick = move |_: MouseEvent| {
+            if enabled {
+                scope.emit(Event::Select(arrangement));
+            }
+        };
+        let style = match enabled {
+            true => "padding: 4px 12px; cursor: pointer",
+            false => "padding: 4px 12px; color: gray",
+        };
+        html! {
+            <div {style} {onclick}>{arrangement.name()}</div>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        let style = format!(
+            "position: absolute; left: {x}px; top: {y}px; background: white; \
+             border: 1px solid gray; box-shadow: 2px 2px 6px rgba(0, 0, 0, 0.3); \
+             font: 14px sans-serif; user-select: none",
+            x = props.pos.x,
+            y = props.pos.y,
+        );
+        // keep clicks inside the menu away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <div {style} {onmousedown}>
+                {Arrangement::ALL.iter().map(|arrangement| Self::item_html(*arrangement, props)).collect::<Html>()}
+            </div>
+        }
+    }
+}
+
+impl yew::Component for ArrangeMenu {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
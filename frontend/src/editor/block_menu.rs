@@ -0,0 +1,81 @@
+use glam::DVec2;
+use yew::{html, Callback, Html, MouseEvent};
+
+use super::board::block::{layer_kind, LayerKind};
+
+pub enum Event {
+    Select(LayerKind),
+}
+
+// position where "n" was pressed, both in app and board coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct State {
+    pub app_pos: DVec2,
+    pub board_pos: DVec2,
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    pub state: State,
+    // boundary and identity blocks are only offered inside of a group
+    pub in_group: bool,
+    pub scope: Callback<Event>,
+}
+
+#[derive(Default)]
+pub struct BlockMenu;
+
+impl BlockMenu {
+    fn item_html(kind: LayerKind, scope: Callback<Event>) -> Html {
+        let onclick = move |_: MouseEvent| scope.emit(Event::Select(kind));
+        let style = format!(
+            "padding: 4px 12px; cursor: pointer; border-left: 6px solid {color}",
+            color = layer_kind::color(kind),
+        );
+        html! {
+            <div {style} {onclick}>{kind.name()}</div>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        let style = format!(
+            "position: absolute; left: {x}px; top: {y}px; background: white; \
+             border: 1px solid gray; box-shadow: 2px 2px 6px rgba(0, 0, 0, 0.3); \
+             font: 14px sans-serif; user-select: none",
+            x = props.state.app_pos.x,
+            y = props.state.app_pos.y,
+        );
+        // keep clicks inside the menu away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <div {style} {onmousedown}>
+                // groups are made from selected blocks
+                {LayerKind::ALL
+                    .iter()
+                    .filter(|kind| !matches!(kind, LayerKind::Group | LayerKind::Op))
+                    .filter(|kind| props.in_group || !matches!(kind, LayerKind::GroupInput | LayerKind::GroupOutput | LayerKind::Identity))
+                    .map(|kind| Self::item_html(*kind, props.scope.clone()))
+                    .collect::<Html>()}
+            </div>
+        }
+    }
+}
+
+impl yew::Component for BlockMenu {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
@@ -1,7 +1,8 @@
-mod arrow;
+pub mod arrow;
 pub mod block;
 mod graph;
 pub mod state;
+pub mod validation;
 mod event;
 
 use yew::{prelude::Context, Properties};
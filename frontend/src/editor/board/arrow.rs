@@ -1,3 +1,6 @@
+pub mod edge_data;
+mod event;
+
 use glam::DVec2;
 
 use yew::{Html, html};
@@ -7,36 +10,67 @@ use block::state::StateInterface;
 
 use crate::editor::board::graph;
 
+pub use edge_data::EdgeData;
+pub use event::Event;
+
 #[derive(Debug)]
 pub struct Arrow<'a> {
+    edge: graph::Edge,
     start: block::state::State<'a>,
     end: block::state::State<'a>,
+    selected: bool,
 }
 
 impl<'a> Arrow<'a> {
-    pub fn from(edge: graph::Edge, vertices: &'a graph::Graph<block::vertex_data::VertexData>) -> Self {
+    // clicks within this distance of the curve hit the arrow
+    const HIT_WIDTH: f64 = 12.0;
+
+    pub fn from(edge: graph::Edge, vertices: &'a super::state::internal::Graph) -> Self {
         Self {
-            start: block::state::State::from(vertices.entry(edge.0).unwrap()),
-            end: block::state::State::from(vertices.entry(edge.1).unwrap()),
+            edge,
+            start: block::state::State::from(vertices.entry(edge.from).unwrap()),
+            end: block::state::State::from(vertices.entry(edge.to).unwrap()),
+            selected: vertices.edge(edge).is_some_and(|data| data.selected),
         }
     }
 
-    fn control_point_in(block: &block::state::State) -> ControlPoint {
+    fn control_point_in(block: &block::state::State, port: graph::Port) -> ControlPoint {
         ControlPoint {
-            point: block.center() + block.size() * DVec2 { x: -0.5, y: 0. },
+            // the arrow ends on the port circle, not under it
+            point: block.input_port_pos(port) - DVec2 { x: block::Block::PORT_RADIUS, y: 0. },
             vector: block.size() * DVec2 { x: -0.5, y: 0. },
         }
     }
-    fn control_point_out(block: &block::state::State) -> ControlPoint {
+    fn control_point_out(block: &block::state::State, port: graph::Port) -> ControlPoint {
         ControlPoint {
-            point: block.center() + block.size() * DVec2 { x: 0.5, y: 0. },
+            point: block.output_port_pos(port) + DVec2 { x: block::Block::PORT_RADIUS, y: 0. },
             vector: block.size() * DVec2 { x: 0.5, y: 0. },
         }
     }
 
-    pub fn html(&self) -> Html {
-        let start = Self::control_point_out(&self.start);
-        let end = Self::control_point_in(&self.end);
+    // the end block can't accept the output of the start one
+    fn mismatch(&self) -> bool {
+        match self.end.shape() {
+            block::InferredShape::Mismatch(error) => error.input == Some(self.start.id()),
+            _ => false,
+        }
+    }
+
+    pub fn html(&self, callback: yew::Callback<Event>) -> Html {
+        let color = match (self.selected, self.mismatch()) {
+            (true, _) => "blue",
+            (false, true) => "red",
+            (false, false) => "black",
+        };
+        let stroke_width = if self.selected { 3 } else { 1 };
+        let edge = self.edge;
+        // other buttons fall through to the board, so the view can be dragged from an arrow
+        let onmousedown = move |e: yew::MouseEvent| if e.button() == 0 {
+            e.stop_immediate_propagation();
+            callback.emit(Event::MouseDown(e, edge))
+        };
+        let start = Self::control_point_out(&self.start, self.edge.from_port);
+        let end = Self::control_point_in(&self.end, self.edge.to_port);
         let path_content = format!("M {} C {}, {}, {}",
                 display_coords_path(&start.point),
                 display_coords_path(&(start.point.clone() + start.vector.clone())),
@@ -45,8 +79,10 @@ impl<'a> Arrow<'a> {
         );
         html!{
             <>
-            <path d={path_content} stroke="black" fill="transparent"/>
-            {triangle_html(&end)}
+            <path d={path_content.clone()} stroke={color} stroke-width={stroke_width.to_string()} fill="transparent"/>
+            <path d={path_content} stroke="transparent" stroke-width={Self::HIT_WIDTH.to_string()} fill="none"
+            style="cursor: pointer" {onmousedown}/>
+            {triangle_html(&end, color)}
             </>
         }
     }
@@ -54,7 +90,7 @@ impl<'a> Arrow<'a> {
 
 impl<'a> PartialEq for Arrow<'a> {
     fn eq(&self, other: &Self) -> bool {
-       self.start.id() == other.start.id() && self.end.id() == other.end.id()
+       self.edge == other.edge
     }
 }
 impl<'a> Eq for Arrow<'a> {}
@@ -75,7 +111,7 @@ fn display_coords_poly(coords: &DVec2) -> String {
 const TR_DX: f64 = 7.0;
 const TR_DY: f64 = TR_DX * 0.5774;
 
-fn triangle_html(cp: &ControlPoint) -> Html {
+fn triangle_html(cp: &ControlPoint, color: &'static str) -> Html {
     // TODO: this triangle ending works only with horizontal and long enough 'end' control vector
     let polygon_points = format!("{} {} {}",
             display_coords_poly(&(cp.point.clone() - DVec2 { x: TR_DX, y: TR_DY })),
@@ -83,6 +119,6 @@ fn triangle_html(cp: &ControlPoint) -> Html {
             display_coords_poly(&cp.point),
     );
     html!{
-        <polygon points={polygon_points} fill="black" stroke-linejoin="round"/>
+        <polygon points={polygon_points} fill={color} stroke-linejoin="round"/>
     }
 }
@@ -0,0 +1,4 @@
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct EdgeData {
+    pub selected: bool,
+}
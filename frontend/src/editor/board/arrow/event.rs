@@ -0,0 +1,7 @@
+use yew::MouseEvent;
+
+use super::graph::Edge;
+
+pub enum Event {
+    MouseDown(MouseEvent, Edge),
+}
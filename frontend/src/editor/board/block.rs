@@ -1,9 +1,18 @@
 pub mod state;
 pub mod event;
+pub mod layer_kind;
+pub mod port;
+pub mod run_result;
+pub mod shape;
 pub(super) mod vertex_data;
 
 pub use state::StateDump;
 pub use event::Event;
+pub use common::params;
+pub use layer_kind::LayerKind;
+pub use port::Port;
+pub use run_result::RunResult;
+pub use shape::InferredShape;
 
 use crate::editor::board::block::state::StateInterface;
 
@@ -19,27 +28,134 @@ pub struct Props {
 pub struct Block;
 
 impl Block {
-    fn get_style(&self, selected: bool) -> String {
-        let stroke_color = if selected { "blue" } else { "black" };
-        let stroke = format!("stroke:{stroke_color};stroke-width:5; stroke-opacity: 0.5");
-        let block_color = if selected { "rgb(100, 100, 255)" } else { "red" };
+    const TITLE_OFFSET: f64 = 30.0;
+    const SHAPE_OFFSET: f64 = 20.0;
+    const RUN_LINE_HEIGHT: f64 = 18.0;
+    pub const PORT_RADIUS: f64 = 8.0;
+
+    fn get_style(&self, selected: bool, kind: LayerKind, mismatch: bool) -> String {
+        let stroke_color = match (selected, mismatch) {
+            (true, _) => "blue",
+            (false, true) => "red",
+            (false, false) => "black",
+        };
+        let stroke_width = if selected { 8 } else { 5 };
+        let stroke = format!("stroke:{stroke_color};stroke-width:{stroke_width}; stroke-opacity: 0.5");
+        let block_color = layer_kind::color(kind);
         format!("fill:{block_color};fill-opacity:0.5;{stroke}")
     }
+    fn title_html(&self, props: &Props) -> yew::Html {
+        let x = props.state.center().x;
+        let y = props.state.top_left().y + Self::TITLE_OFFSET;
+        // instances of library components go by the component name, imported ops by their type
+        let title = match (props.state.component(), props.state.params().text(params::OP_TYPE)) {
+            (Some(component), _) => component.name,
+            (None, Some(op)) => op.to_string(),
+            (None, None) => props.state.kind().name().to_string(),
+        };
+        yew::html! {
+            <text x={x.to_string()} y={y.to_string()} text-anchor="middle"
+            style="font: bold 20px sans-serif; user-select: none; pointer-events: none">
+                {title}
+            </text>
+        }
+    }
+    fn shape_html(&self, props: &Props) -> yew::Html {
+        let x = props.state.center().x.to_string();
+        let y = (props.state.bottom_right().y - Self::SHAPE_OFFSET).to_string();
+        match props.state.shape() {
+            InferredShape::Unknown => yew::html! {},
+            InferredShape::Known(shape) => yew::html! {
+                <text {x} {y} text-anchor="middle"
+                style="font: 16px monospace; user-select: none; pointer-events: none">
+                    {shape.to_string()}
+                </text>
+            },
+            InferredShape::Mismatch(error) => yew::html! {
+                <text {x} {y} text-anchor="middle" fill="red"
+                style="font: bold 16px sans-serif; user-select: none">
+                    <title>{error.message}</title>
+                    {"shape mismatch"}
+                </text>
+            },
+        }
+    }
+    // forward pass results, printed under the block
+    fn run_result_html(&self, props: &Props) -> yew::Html {
+        let x = props.state.center().x;
+        let y = props.state.bottom_right().y;
+        let lines = match props.state.run_result() {
+            None => return yew::html! {},
+            Some(RunResult::Stats(stats)) => vec![
+                format!("{:?}", stats.shape),
+                format!("mean {:.3} std {:.3}", stats.mean, stats.std),
+                format!("min {:.3} max {:.3}", stats.min, stats.max),
+            ],
+            Some(RunResult::Failed(message)) => return yew::html! {
+                <text x={x.to_string()} y={(y + Self::RUN_LINE_HEIGHT).to_string()} text-anchor="middle" fill="red"
+                style="font: bold 14px sans-serif; user-select: none">
+                    <title>{message}</title>
+                    {"forward pass failed"}
+                </text>
+            },
+        };
+        lines.into_iter().enumerate().map(|(i, line)| yew::html! {
+            <text x={x.to_string()} y={(y + Self::RUN_LINE_HEIGHT * (i + 1) as f64).to_string()} text-anchor="middle"
+            style="font: 14px monospace; user-select: none; pointer-events: none">
+                {line}
+            </text>
+        }).collect::<yew::Html>()
+    }
+    fn port_html(&self, props: &Props, port: Port, name: String) -> yew::Html {
+        let pos = match port {
+            Port::Input(index) => props.state.input_port_pos(index),
+            Port::Output(index) => props.state.output_port_pos(index),
+        };
+        let scope = props.scope.clone();
+        let id = props.state.id();
+        // other buttons reach the block itself
+        let onmousedown = move |e: yew::MouseEvent| if e.button() == 0 {
+            e.stop_immediate_propagation();
+            scope.emit(Event::PortMouseDown(id, port))
+        };
+        yew::html! {
+            <circle cx={pos.x.to_string()} cy={pos.y.to_string()} r={Self::PORT_RADIUS.to_string()}
+            fill="white" stroke="black" stroke-width="2" style="cursor: crosshair" {onmousedown}>
+                <title>{name}</title>
+            </circle>
+        }
+    }
+    fn ports_html(&self, props: &Props) -> yew::Html {
+        let inputs = props.state.input_ports().into_iter().enumerate().map(|(index, name)| self.port_html(props, Port::Input(index), name));
+        let outputs = props.state.output_ports().into_iter().enumerate().map(|(index, name)| self.port_html(props, Port::Output(index), name));
+        inputs.chain(outputs).collect::<yew::Html>()
+    }
     pub fn html(&self, props: &Props) -> yew::Html {
-        let style = self.get_style(props.state.selected());
+        let mismatch = matches!(props.state.shape(), InferredShape::Mismatch(_));
+        let style = self.get_style(props.state.selected(), props.state.kind(), mismatch);
         let scope = props.scope.clone();
         let id = props.state.id().clone();
         let onmousedown = move |e: yew::MouseEvent| {
             e.stop_immediate_propagation();
             scope.emit(Event::MouseDown(e, id))
         };
+        let scope = props.scope.clone();
+        let ondblclick = move |e: yew::MouseEvent| {
+            e.stop_immediate_propagation();
+            scope.emit(Event::DoubleClick(id))
+        };
         yew::html! {
             <g
             onmousedown={onmousedown}
+            ondblclick={ondblclick}
             >
                 <rect x={props.state.top_left().x.to_string()} y={props.state.top_left().y.to_string()}
                 rx="20" ry="20" width={props.state.size().x.to_string()} height={props.state.size().y.to_string()}
                 style={style}/>
+                {self.title_html(props)}
+                {self.shape_html(props)}
+                {self.run_result_html(props)}
+                {self.ports_html(props)}
             </g>
         }
     }
@@ -2,6 +2,10 @@ use yew::{MouseEvent};
 
 use crate::tools::Id;
 
+use super::port::Port;
+
 pub enum Event {
     MouseDown(MouseEvent, Id),
+    PortMouseDown(Id, Port),
+    DoubleClick(Id),
 }
@@ -0,0 +1,21 @@
+pub use common::layer::LayerKind;
+
+// fill color of the block on the board
+pub fn color(kind: LayerKind) -> &'static str {
+    match kind {
+        LayerKind::Input | LayerKind::Output => "rgb(120, 120, 120)",
+        LayerKind::Linear => "rgb(230, 80, 80)",
+        LayerKind::Conv2d => "rgb(240, 150, 50)",
+        LayerKind::MaxPool2d => "rgb(200, 180, 60)",
+        LayerKind::BatchNorm => "rgb(70, 170, 170)",
+        LayerKind::ReLU | LayerKind::LeakyReLU => "rgb(90, 180, 90)",
+        LayerKind::Dropout => "rgb(160, 110, 200)",
+        LayerKind::Flatten => "rgb(150, 120, 90)",
+        LayerKind::Softmax => "rgb(80, 140, 220)",
+        LayerKind::Add | LayerKind::Concat => "rgb(200, 100, 160)",
+        LayerKind::Identity => "rgb(200, 200, 200)",
+        LayerKind::Op => "rgb(230, 230, 230)",
+        LayerKind::Group => "rgb(110, 130, 200)",
+        LayerKind::GroupInput | LayerKind::GroupOutput => "rgb(170, 180, 220)",
+    }
+}
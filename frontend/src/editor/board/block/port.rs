@@ -0,0 +1,8 @@
+pub use common::graph::Port as Index;
+
+// a port on one of the block edges, inputs on the left and outputs on the right
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Port {
+    Input(Index),
+    Output(Index),
+}
@@ -0,0 +1,8 @@
+use common::api::BlockStats;
+
+// outcome of the last forward pass for a block
+#[derive(PartialEq, Debug, Clone)]
+pub enum RunResult {
+    Stats(BlockStats),
+    Failed(String),
+}
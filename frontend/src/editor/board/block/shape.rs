@@ -0,0 +1,10 @@
+pub use common::shape::{infer, Shape, ShapeError};
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub enum InferredShape {
+    // not connected to any Input block or some input is not inferred
+    #[default]
+    Unknown,
+    Known(Shape),
+    Mismatch(ShapeError),
+}
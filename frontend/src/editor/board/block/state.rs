@@ -1,8 +1,11 @@
+use std::rc::Rc;
+
 use glam::DVec2;
 
 use crate::editor::board::graph::OccupiedVertexEntry;
 
 type BlockId = crate::editor::board::block::Id;
+type Graph = crate::editor::board::state::internal::Graph;
 
 const BLOCK_SIZE: DVec2 = DVec2{x: 150.0, y: 150.0};
 
@@ -25,6 +28,7 @@ impl<'a> State<'a> {
         &self.0
     }
 
+    #[allow(unused)]
     pub fn entry_mut(&mut self) -> &mut OccupiedVertexEntry<'a, super::vertex_data::VertexData> {
         &mut self.0
     }
@@ -38,14 +42,34 @@ pub trait StateInterface {
     fn set_selected(&mut self, selected: bool);
     #[allow(unused)]
     fn toggle_selected(&mut self) -> bool;
+    fn set_param(&mut self, name: &str, value: super::params::ParamValue);
+    fn set_shape(&mut self, shape: super::shape::InferredShape);
+    fn set_run_result(&mut self, run_result: Option<super::RunResult>);
+    fn set_group(&mut self, group: Rc<Graph>);
+    fn set_component(&mut self, component: Option<common::library::ComponentRef>);
     
     fn id(&self) -> BlockId;
     fn selected(&self) -> bool;
+    fn kind(&self) -> super::LayerKind;
+    fn params(&self) -> super::params::Params;
+    fn shape(&self) -> super::shape::InferredShape;
+    fn run_result(&self) -> Option<super::RunResult>;
+    fn group(&self) -> Option<Rc<Graph>>;
+    fn component(&self) -> Option<common::library::ComponentRef>;
+    fn input_ports(&self) -> Vec<String>;
+    fn output_ports(&self) -> Vec<String>;
     fn size(&self) -> DVec2;
 
     fn top_left(&self) -> DVec2;
     fn bottom_right(&self) -> DVec2;
     fn center(&self) -> DVec2;
+    fn input_port_pos(&self, port: super::port::Index) -> DVec2;
+    fn output_port_pos(&self, port: super::port::Index) -> DVec2;
+}
+
+// ports are spread evenly along the block edge
+fn port_y(top: f64, height: f64, port: super::port::Index, count: usize) -> f64 {
+    top + height * (port + 1) as f64 / (count + 1) as f64
 }
   
 trait StateImpl {
@@ -65,9 +89,47 @@ impl<T: StateImpl> StateInterface for T {
         self.vertex_data_mut().selected = !self.selected();
         self.selected()
     }
+    fn set_param(&mut self, name: &str, value: super::params::ParamValue) {
+        self.vertex_data_mut().params.set(name, value);
+    }
+    fn set_shape(&mut self, shape: super::shape::InferredShape) {
+        self.vertex_data_mut().shape = shape;
+    }
+    fn set_run_result(&mut self, run_result: Option<super::RunResult>) {
+        self.vertex_data_mut().run_result = run_result;
+    }
+    fn set_group(&mut self, group: Rc<Graph>) {
+        self.vertex_data_mut().group = Some(group);
+    }
+    fn set_component(&mut self, component: Option<common::library::ComponentRef>) {
+        self.vertex_data_mut().component = component;
+    }
     
     fn id(&self) -> BlockId { self.id() }
     fn selected(&self) -> bool { self.vertex_data().selected }
+    fn kind(&self) -> super::LayerKind { self.vertex_data().kind }
+    fn params(&self) -> super::params::Params { self.vertex_data().params.clone() }
+    fn shape(&self) -> super::shape::InferredShape { self.vertex_data().shape.clone() }
+    fn run_result(&self) -> Option<super::RunResult> { self.vertex_data().run_result.clone() }
+    fn group(&self) -> Option<Rc<Graph>> { self.vertex_data().group.clone() }
+    fn component(&self) -> Option<common::library::ComponentRef> { self.vertex_data().component.clone() }
+    // a group has a port for every GroupInput block inside and an Op block the ports of its node, as in `common::graph::Vertex`
+    fn input_ports(&self) -> Vec<String> {
+        match self.group() {
+            Some(group) if self.kind() == super::LayerKind::Group => {
+                let count = group.iter_vertices().filter(|block| block.data().kind == super::LayerKind::GroupInput).count();
+                (0..count).map(common::layer::group_input_port).collect()
+            }
+            _ if self.kind() == super::LayerKind::Op => common::layer::op_input_ports(&self.vertex_data().params),
+            _ => self.kind().input_ports().iter().map(|name| name.to_string()).collect(),
+        }
+    }
+    fn output_ports(&self) -> Vec<String> {
+        match self.kind() {
+            super::LayerKind::Op => common::layer::op_output_ports(&self.vertex_data().params),
+            _ => self.kind().output_ports().iter().map(|name| name.to_string()).collect(),
+        }
+    }
     fn size(&self) -> DVec2 { BLOCK_SIZE }
 
     fn top_left(&self) -> DVec2 {
@@ -79,6 +141,14 @@ impl<T: StateImpl> StateInterface for T {
     fn center(&self) -> DVec2 {
         self.vertex_data().center
     }
+    fn input_port_pos(&self, port: super::port::Index) -> DVec2 {
+        let count = self.input_ports().len();
+        DVec2 { x: self.top_left().x, y: port_y(self.top_left().y, self.size().y, port, count) }
+    }
+    fn output_port_pos(&self, port: super::port::Index) -> DVec2 {
+        let count = self.output_ports().len();
+        DVec2 { x: self.bottom_right().x, y: port_y(self.top_left().y, self.size().y, port, count) }
+    }
 }
 
 impl<'a> StateImpl for State<'a> {
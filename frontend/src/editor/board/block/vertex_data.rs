@@ -1,13 +1,43 @@
+use std::rc::Rc;
+
 use glam::DVec2;
 
+use super::layer_kind::LayerKind;
+use super::params::Params;
+use super::run_result::RunResult;
+use super::shape::InferredShape;
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct VertexData {
     pub(super) center: DVec2,
     pub(super) selected: bool,
+    pub(super) kind: LayerKind,
+    pub(super) params: Params,
+    pub(super) shape: InferredShape,
+    pub(super) run_result: Option<RunResult>,
+    // contents of a Group block, shared with the undo history
+    pub(super) group: Option<Rc<crate::editor::board::state::internal::Graph>>,
+    // library component the group was made from
+    pub(super) component: Option<common::library::ComponentRef>,
 }
 
 impl VertexData {
-    pub fn from(center: DVec2) -> Self {
-        Self { center, selected: false }
+    pub fn from(center: DVec2, kind: LayerKind) -> Self {
+        Self { center, selected: false, kind, params: Params::defaults(kind), shape: InferredShape::Unknown, run_result: None, group: None, component: None }
+    }
+
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn with_group(mut self, group: Option<Rc<crate::editor::board::state::internal::Graph>>) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn with_component(mut self, component: Option<common::library::ComponentRef>) -> Self {
+        self.component = component;
+        self
     }
 }
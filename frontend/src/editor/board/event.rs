@@ -1,3 +1,4 @@
 pub enum Event {
     BlockEvent(super::block::Event),
+    ArrowEvent(super::arrow::Event),
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::tools;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::cell::RefCell;
 
 mod basic;
@@ -12,21 +12,31 @@ pub use vertex::*;
 pub use vertex_entry::*;
 
 #[derive(Debug)]
-pub struct Graph<VertexData> {
+pub struct Graph<VertexData, EdgeData = ()> {
     id_gen: tools::IdGen,
     vertices: BTreeMap<VertexId, RefCell<Vertex<VertexData>>>,
+    // kept in sync with the incoming and outgoing sets of the vertices
+    edges: BTreeMap<Edge, EdgeData>,
 }
 
-impl<VertexData> Default for Graph<VertexData> {
+impl<VertexData, EdgeData> Default for Graph<VertexData, EdgeData> {
     fn default() -> Self {
         Self {
             id_gen: tools::IdGen::default(),
             vertices: BTreeMap::default(),
+            edges: BTreeMap::default(),
         }
     }
 }
 
-impl<VertexData> Graph<VertexData> {
+impl<VertexData, EdgeData> Graph<VertexData, EdgeData> {
+    // empty graph that gives out ids after the given one
+    pub fn with_last_id(last: VertexId) -> Self {
+        Self { id_gen: tools::IdGen::after(last), ..Self::default() }
+    }
+    pub fn last_id(&self) -> VertexId {
+        self.id_gen.last_id()
+    }
     pub fn entry(&self, id: VertexId) -> Option<OccupiedVertexEntry<VertexData>> {
         match self.vertices.contains_key(&id) {
             true => Some(OccupiedVertexEntry::new(id, &self.vertices)),
@@ -40,20 +50,128 @@ impl<VertexData> Graph<VertexData> {
         let id = self.id_gen.next().unwrap();
         self.entry_mut(id).occupy(data).unwrap()
     }
+    // adds a vertex with a known id, as when restoring a saved graph
+    pub fn insert_vertex(&mut self, id: VertexId, data: VertexData) -> Option<OccupiedVertexEntry<VertexData>> {
+        if id > self.id_gen.last_id() {
+            self.id_gen = tools::IdGen::after(id);
+        }
+        self.entry_mut(id).occupy(data)
+    }
     pub fn remove_vertex(&mut self, id: VertexId) {
         let mut vertex = self.vertices.remove(&id).unwrap().into_inner();
     
         vertex.incoming.remove(&id);
         vertex.outgoing.remove(&id);
+        self.edges.retain(|edge, _| edge.from != id && edge.to != id);
         vertex.incoming.into_iter().for_each(|id| {self.vertices.get_mut(&id).unwrap().borrow_mut().outgoing.remove(&vertex.id);});
         vertex.outgoing.into_iter().for_each(|id| {self.vertices.get_mut(&id).unwrap().borrow_mut().incoming.remove(&vertex.id);});
     }
 
-    #[allow(unused)]
-    pub fn add_edge(&mut self, e: Edge) {
-        self.entry_mut(e.0).unwrap().add_outgoing(e.1);
+    pub fn add_edge(&mut self, e: Edge) where EdgeData: Default {
+        self.entry_mut(e.from).unwrap().add_outgoing(e.to);
+        self.edges.entry(e).or_default();
+    }
+
+    pub fn remove_edge(&mut self, e: Edge) -> Option<EdgeData> {
+        let data = self.edges.remove(&e)?;
+        // vertices stay adjacent while other ports connect them
+        if !self.edges.keys().any(|edge| edge.from == e.from && edge.to == e.to) {
+            self.entry_mut(e.from).unwrap().remove_outgoing(e.to);
+        }
+        Some(data)
+    }
+
+    // edges into the vertex, in port order
+    pub fn incoming_edges(&self, id: VertexId) -> Vec<Edge> {
+        let mut edges = self.edges.keys().filter(|edge| edge.to == id).copied().collect::<Vec<_>>();
+        edges.sort_by_key(|edge| edge.to_port);
+        edges
+    }
+
+    pub fn edge(&self, e: Edge) -> Option<&EdgeData> {
+        self.edges.get(&e)
+    }
+
+    pub fn edge_mut(&mut self, e: Edge) -> Option<&mut EdgeData> {
+        self.edges.get_mut(&e)
+    }
+
+    pub fn iter_mut_edges_data(&mut self) -> impl Iterator<Item = (&Edge, &mut EdgeData)> {
+        self.edges.iter_mut()
+    }
+
+
+    // Kahn's algorithm, vertices on cycles and everything after them are left out
+    pub fn topological_order(&self) -> Vec<VertexId> {
+        let mut in_degree = self.vertices
+            .iter()
+            .map(|(id, vertex)| (*id, vertex.borrow().incoming.len()))
+            .collect::<BTreeMap<_, _>>();
+        let mut queue = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.vertices.len());
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            self.vertices[&id].borrow().outgoing.iter().for_each(|next| {
+                let degree = in_degree.get_mut(next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(*next);
+                }
+            });
+        }
+        order
     }
 
+    // vertices of some cycle in the order of its edges, the first one is not repeated at the end
+    pub fn find_cycle(&self) -> Option<Vec<VertexId>> {
+        // vertices on the current depth-first path and the ones fully explored
+        let mut path = Vec::<VertexId>::new();
+        let mut done = BTreeSet::<VertexId>::new();
+        for root in self.vertices.keys() {
+            if done.contains(root) {
+                continue;
+            }
+            let mut stack = vec![(*root, false)];
+            while let Some((id, leaving)) = stack.pop() {
+                if leaving {
+                    path.pop();
+                    done.insert(id);
+                    continue;
+                }
+                if done.contains(&id) {
+                    continue;
+                }
+                if let Some(start) = path.iter().position(|on_path| *on_path == id) {
+                    return Some(path[start..].to_vec());
+                }
+                path.push(id);
+                stack.push((id, true));
+                stack.extend(self.vertices[&id].borrow().outgoing.iter().rev().map(|next| (*next, false)));
+            }
+        }
+        None
+    }
+
+    // every vertex is reachable from itself
+    pub fn is_reachable(&self, from: VertexId, to: VertexId) -> bool {
+        let mut visited = BTreeSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            if id == to {
+                return true;
+            }
+            self.vertices[&id].borrow().outgoing.iter().for_each(|next| {
+                if visited.insert(*next) {
+                    queue.push_back(*next);
+                }
+            });
+        }
+        false
+    }
 
     pub fn iter_vertices(&self) -> impl Iterator<Item = OccupiedVertexEntry<VertexData>> {
         iter::VerticesIter::new(self.vertices.iter().map(|(id, _)| *id), &self.vertices)
@@ -64,27 +182,86 @@ impl<VertexData> Graph<VertexData> {
         iter::VerticesIterMut::new(self.vertices.keys().map(|id| *id), &self.vertices)
     }
 
-    pub fn iter_edges(&self) -> impl Iterator<Item = Edge> + use<'_, VertexData> {
-        self.vertices
-            .iter()
-            .flat_map(|(id, vertex)|
-                vertex.borrow()
-                      .outgoing
-                      .iter()
-                      .map(move |other_id| (*id, *other_id))
-                      .collect::<Vec<_>>()
-            )
+    pub fn iter_edges(&self) -> impl Iterator<Item = Edge> + use<'_, VertexData, EdgeData> {
+        self.edges.keys().copied()
     }
 }
 
-impl<VertexData: Clone> Clone for Graph<VertexData> { 
+impl<VertexData: Clone, EdgeData: Clone> Clone for Graph<VertexData, EdgeData> { 
     fn clone(&self) -> Self {
-        Self { id_gen: self.id_gen.clone(), vertices: self.vertices.iter().map(|(k, v)| (k.clone(), RefCell::new(v.borrow().clone()))).collect::<BTreeMap<VertexId, RefCell<Vertex<VertexData>>>>() }
+        Self { id_gen: self.id_gen.clone(), edges: self.edges.clone(), vertices: self.vertices.iter().map(|(k, v)| (k.clone(), RefCell::new(v.borrow().clone()))).collect::<BTreeMap<VertexId, RefCell<Vertex<VertexData>>>>() }
     }
 }
 
-impl<VertexData> PartialEq for Graph<VertexData> {
+// arrows and their selection count too, so changing only them still rerenders and makes an undo step
+impl<VertexData, EdgeData: PartialEq> PartialEq for Graph<VertexData, EdgeData> {
     fn eq(&self, other: &Self) -> bool {
-        self.vertices == other.vertices
+        self.vertices == other.vertices && self.edges == other.edges
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // vertices 1..=count joined by the given edges, every edge through its first ports
+    fn graph(count: usize, edges: &[(VertexId, VertexId)]) -> Graph<()> {
+        let mut graph = Graph::default();
+        (0..count).for_each(|_| { graph.new_vertex(()); });
+        edges.iter().for_each(|(from, to)| graph.add_edge(Edge::new(*from, 0, *to, 0)));
+        graph
+    }
+
+    #[test]
+    fn self_loop_is_a_cycle() {
+        let graph = graph(2, &[(1, 2), (2, 2)]);
+        assert_eq!(graph.find_cycle(), Some(vec![2]));
+        assert_eq!(graph.topological_order(), vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn two_cycle_is_found() {
+        let graph = graph(3, &[(1, 2), (2, 3), (3, 2)]);
+        assert_eq!(graph.find_cycle(), Some(vec![2, 3]));
+    }
+
+    #[test]
+    fn diamond_is_not_a_cycle() {
+        let graph = graph(4, &[(1, 2), (1, 3), (2, 4), (3, 4)]);
+        assert_eq!(graph.find_cycle(), None);
+        assert_eq!(graph.topological_order(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn reachability_follows_the_edges() {
+        let graph = graph(4, &[(1, 2), (2, 3)]);
+        assert!(graph.is_reachable(1, 3));
+        assert!(!graph.is_reachable(3, 1));
+        assert!(graph.is_reachable(4, 4));
+        assert!(!graph.is_reachable(1, 4));
+    }
+
+    #[test]
+    fn topological_order_counts_parallel_port_edges_once() {
+        let mut graph = graph(3, &[(1, 2), (2, 3)]);
+        // an Add fed twice from the same block
+        graph.add_edge(Edge::new(1, 0, 2, 1));
+        graph.add_edge(Edge::new(1, 0, 3, 1));
+        assert_eq!(graph.topological_order(), vec![1, 2, 3]);
+        assert_eq!(graph.find_cycle(), None);
+    }
+
+    #[test]
+    fn removing_one_of_parallel_edges_keeps_the_vertices_adjacent() {
+        let mut graph = graph(2, &[(1, 2)]);
+        graph.add_edge(Edge::new(1, 0, 2, 1));
+        assert_eq!(graph.remove_edge(Edge::new(1, 0, 2, 0)), Some(()));
+        assert!(graph.vertices[&1].borrow().outgoing.contains(&2));
+        assert!(graph.vertices[&2].borrow().incoming.contains(&1));
+        assert_eq!(graph.incoming_edges(2), vec![Edge::new(1, 0, 2, 1)]);
+        // the last edge between them takes the adjacency along
+        assert_eq!(graph.remove_edge(Edge::new(1, 0, 2, 1)), Some(()));
+        assert!(graph.vertices[&1].borrow().outgoing.is_empty());
+        assert!(graph.vertices[&2].borrow().incoming.is_empty());
+        assert_eq!(graph.remove_edge(Edge::new(1, 0, 2, 1)), None);
+    }
+}
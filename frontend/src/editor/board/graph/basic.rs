@@ -1,2 +1,2 @@
 pub type VertexId = crate::tools::Id;
-pub type Edge = (VertexId, VertexId);
+pub use common::graph::{Edge, Port};
@@ -66,15 +66,15 @@ impl<'a, Data> OccupiedVertexEntry<'a, Data> {
     RefMut::map(self.vertex_mut(), |v| &mut v.data)
   }
 
-  #[allow(unused)]
-  pub fn add_incoming(&mut self, id: VertexId) {
-    self.vertex_mut().incoming.insert(id);
-    self.vertices[&id].borrow_mut().outgoing.insert(self.vertex_id);
-  }
-  pub fn add_outgoing(&mut self, id: VertexId) {
+  // edges are changed through the graph, which also keeps their data
+  pub(super) fn add_outgoing(&mut self, id: VertexId) {
     self.vertex_mut().outgoing.insert(id);
     self.vertices[&id].borrow_mut().incoming.insert(self.vertex_id);
   }
+  pub(super) fn remove_outgoing(&mut self, id: VertexId) {
+    self.vertex_mut().outgoing.remove(&id);
+    self.vertices[&id].borrow_mut().incoming.remove(&self.vertex_id);
+  }
 }
 
 impl<'a, Data: std::fmt::Debug> std::fmt::Debug for OccupiedVertexEntry<'a, Data> {
@@ -1,7 +1,11 @@
+pub mod alignment;
+pub mod animation;
+pub mod arrangement;
 pub mod arrow_creation;
 pub mod basic;
 pub mod internal;
 pub mod dragging_blocks;
+pub mod history;
 pub mod predrag;
 pub mod rectangle_selection;
 
@@ -24,27 +28,58 @@ impl Default for State {
 }
 
 impl State {
-    #[allow(unused)]
     const GRID_SIZE: f64 = 80.0;
+    // blocks snap to the lines of the small grid
+    pub const SNAP_SIZE: f64 = Self::GRID_SIZE / 4.0;
 
-    #[allow(unused)]
-    fn grid_html() -> yew::Html {
+    // the nearest point on the small grid
+    pub fn snapped(pos: glam::DVec2) -> glam::DVec2 {
+        (pos / Self::SNAP_SIZE).round() * Self::SNAP_SIZE
+    }
+
+    // the patterns are anchored at the board origin, the rect covers the visible area
+    pub fn grid_html(top_left: glam::DVec2, size: glam::DVec2) -> yew::Html {
         yew::html! {
         <>
         <defs>
-            <pattern id="smallGrid" width={(Self::GRID_SIZE / 10.0).to_string()} height={(Self::GRID_SIZE / 10.0).to_string()} patternUnits="userSpaceOnUse">
-            <path d={format!("M {small_grid_size} 0 L 0 0 0 {small_grid_size}", small_grid_size=Self::GRID_SIZE / 10.0)} fill="none" stroke="gray" stroke-width="0.5"/>
+            <pattern id="smallGrid" width={Self::SNAP_SIZE.to_string()} height={Self::SNAP_SIZE.to_string()} patternUnits="userSpaceOnUse">
+            <path d={format!("M {small_grid_size} 0 L 0 0 0 {small_grid_size}", small_grid_size=Self::SNAP_SIZE)} fill="none" stroke="gray" stroke-width="0.5"/>
             </pattern>
             <pattern id="grid" width={Self::GRID_SIZE.to_string()} height={Self::GRID_SIZE.to_string()} patternUnits="userSpaceOnUse">
             <rect width={Self::GRID_SIZE.to_string()} height={Self::GRID_SIZE.to_string()} fill="url(#smallGrid)"/>
             <path d={format!("M {grid_size} 0 L 0 0 0 {grid_size}", grid_size=Self::GRID_SIZE)} fill="none" stroke="gray" stroke-width="1"/>
             </pattern>
             </defs>
-            <rect width="100%" height="100%" x="0" y="0" fill="url(#grid)" />
+            <rect width={size.x.to_string()} height={size.y.to_string()} x={top_left.x.to_string()} y={top_left.y.to_string()} fill="url(#grid)" />
         </>
         }
     }
 
+    // a loaded board starts with nothing in progress
+    pub fn from_project(project: &common::project::Project) -> Self {
+        State::Basic(basic::State::from(internal::State::from_project(project)))
+    }
+
+    pub fn internal(&self) -> &internal::State {
+        match self {
+            State::Basic(state) => state.internal(),
+            State::PredragBlocks(state) => state.internal(),
+            State::DraggingBlocks(state) => state.internal(),
+            State::RectangleSelection(state) => state.internal(),
+            State::ArrowCreation(state) => state.internal(),
+        }
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        match self {
+            State::Basic(state) => state.internal_mut(),
+            State::PredragBlocks(state) => state.internal_mut(),
+            State::DraggingBlocks(state) => state.internal_mut(),
+            State::RectangleSelection(state) => state.internal_mut(),
+            State::ArrowCreation(state) => state.internal_mut(),
+        }
+    }
+
     pub fn set_new_state(&mut self, new_state: Self) -> &mut Self {
         *self = new_state;
         log::debug!("New board state: {}", self);
@@ -0,0 +1,97 @@
+use glam::DVec2;
+
+use crate::editor::board::block::{self, state::StateInterface};
+
+use super::internal;
+
+// distance in board units from which a dragged block lines up with another one
+const ALIGN_DISTANCE: f64 = 10.0;
+// positions closer than this count as lined up
+const EPSILON: f64 = 1e-6;
+
+// a dashed line through the blocks lined up with the dragged one
+#[derive(Debug, PartialEq, Clone)]
+pub struct Guide {
+    from: DVec2,
+    to: DVec2,
+}
+
+impl Guide {
+    pub fn html(&self) -> yew::Html {
+        yew::html! {
+            <line x1={self.from.x.to_string()} y1={self.from.y.to_string()}
+                  x2={self.to.x.to_string()} y2={self.to.y.to_string()}
+                  style="stroke:rgb(255,0,255);stroke-width:1;stroke-dasharray:6 4"/>
+        }
+    }
+}
+
+// left edge, center and right edge, or top, center and bottom along y
+fn lines(top_left: DVec2, bottom_right: DVec2, along_y: bool) -> [f64; 3] {
+    let (start, end) = match along_y {
+        false => (top_left.x, bottom_right.x),
+        true => (top_left.y, bottom_right.y),
+    };
+    [start, (start + end) / 2., end]
+}
+
+// corners of the blocks that stay in place while the selection is dragged
+fn still_blocks(internal: &internal::State) -> Vec<(DVec2, DVec2)> {
+    internal.graph()
+        .iter_vertices()
+        .map(block::state::State::from)
+        .filter(|block| !block.selected())
+        .map(|block| (block.top_left(), block.bottom_right()))
+        .collect()
+}
+
+// edges line up with edges, centers with centers
+fn aligned(mine: [f64; 3], theirs: [f64; 3]) -> impl Iterator<Item = f64> {
+    [(0, 0), (1, 1), (2, 2), (0, 2), (2, 0)].into_iter().map(move |(a, b)| theirs[b] - mine[a])
+}
+
+// center of the held block moved by less than `ALIGN_DISTANCE` to line up with a block left in place,
+// on every axis where there is one
+pub fn align(internal: &internal::State, held: block::Id, center: DVec2) -> (Option<f64>, Option<f64>) {
+    let half = internal.block(held).size() / 2.;
+    let others = still_blocks(internal);
+    let axis = |along_y: bool| {
+        let mine = lines(center - half, center + half, along_y);
+        others
+            .iter()
+            .flat_map(|(top_left, bottom_right)| aligned(mine, lines(*top_left, *bottom_right, along_y)))
+            .filter(|shift| shift.abs() < ALIGN_DISTANCE)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+            .map(|shift| if along_y { center.y + shift } else { center.x + shift })
+    };
+    (axis(false), axis(true))
+}
+
+// a guide for every line of the held block some other block lines up with, spanning all of them
+pub fn guides(internal: &internal::State, held: block::Id) -> Vec<Guide> {
+    let block = internal.block(held);
+    let held_corners = (block.top_left(), block.bottom_right());
+    let others = still_blocks(internal);
+    let mut guides = Vec::new();
+    for along_y in [false, true] {
+        let mine = lines(held_corners.0, held_corners.1, along_y);
+        for line in mine {
+            let lined_up = others
+                .iter()
+                .filter(|(top_left, bottom_right)| lines(*top_left, *bottom_right, along_y).iter().any(|their| (their - line).abs() < EPSILON))
+                .chain(std::iter::once(&held_corners))
+                .collect::<Vec<_>>();
+            if lined_up.len() < 2 {
+                continue;
+            }
+            // the guide runs across the other axis
+            let start = lined_up.iter().map(|(top_left, _)| if along_y { top_left.x } else { top_left.y }).fold(f64::INFINITY, f64::min);
+            let end = lined_up.iter().map(|(_, bottom_right)| if along_y { bottom_right.x } else { bottom_right.y }).fold(f64::NEG_INFINITY, f64::max);
+            guides.push(match along_y {
+                false => Guide { from: DVec2::new(line, start), to: DVec2::new(line, end) },
+                true => Guide { from: DVec2::new(start, line), to: DVec2::new(end, line) },
+            });
+        }
+    }
+    guides
+}
@@ -0,0 +1,36 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use glam::DVec2;
+
+use crate::editor::board::block;
+
+// about a third of a second at the usual refresh rate
+pub const FRAMES: usize = 20;
+pub const FRAME_TIME: Duration = Duration::from_millis(16);
+
+// blocks gliding from their old centers to new ones
+#[derive(Debug, PartialEq, Clone)]
+pub struct Animation {
+    from: BTreeMap<block::Id, DVec2>,
+    to: BTreeMap<block::Id, DVec2>,
+    frame: usize,
+}
+
+impl Animation {
+    pub fn new(from: BTreeMap<block::Id, DVec2>, to: BTreeMap<block::Id, DVec2>) -> Self {
+        Self { from, to, frame: 0 }
+    }
+
+    // centers for the next frame, None once the blocks arrived
+    pub fn next(&mut self) -> Option<BTreeMap<block::Id, DVec2>> {
+        if self.frame == FRAMES {
+            return None;
+        }
+        self.frame += 1;
+        let t = self.frame as f64 / FRAMES as f64;
+        // slow at both ends
+        let eased = t * t * (3. - 2. * t);
+        Some(self.to.iter().map(|(id, to)| (*id, self.from[id].lerp(*to, eased))).collect())
+    }
+}
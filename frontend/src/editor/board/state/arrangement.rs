@@ -0,0 +1,158 @@
+use glam::DVec2;
+
+// ways to line up or space out the selected blocks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arrangement {
+    AlignLeft,
+    AlignRight,
+    AlignTop,
+    AlignBottom,
+    // centers on one vertical line
+    AlignCenter,
+    // centers on one horizontal line
+    AlignMiddle,
+    DistributeHorizontally,
+    DistributeVertically,
+}
+
+impl Arrangement {
+    pub const ALL: &'static [Arrangement] = &[
+        Self::AlignLeft,
+        Self::AlignRight,
+        Self::AlignTop,
+        Self::AlignBottom,
+        Self::AlignCenter,
+        Self::AlignMiddle,
+        Self::DistributeHorizontally,
+        Self::DistributeVertically,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AlignLeft => "Align left",
+            Self::AlignRight => "Align right",
+            Self::AlignTop => "Align top",
+            Self::AlignBottom => "Align bottom",
+            Self::AlignCenter => "Align centers",
+            Self::AlignMiddle => "Align middles",
+            Self::DistributeHorizontally => "Distribute horizontally",
+            Self::DistributeVertically => "Distribute vertically",
+        }
+    }
+
+    // Alt with an arrow aligns to that side, with C or M the centers, with H or V the blocks are distributed;
+    // codes are used since Alt changes the typed character on some layouts
+    pub fn from_shortcut(event: &yew::KeyboardEvent) -> Option<Self> {
+        if !event.alt_key() || event.ctrl_key() {
+            return None;
+        }
+        match event.code().as_str() {
+            "ArrowLeft" => Some(Self::AlignLeft),
+            "ArrowRight" => Some(Self::AlignRight),
+            "ArrowUp" => Some(Self::AlignTop),
+            "ArrowDown" => Some(Self::AlignBottom),
+            "KeyC" => Some(Self::AlignCenter),
+            "KeyM" => Some(Self::AlignMiddle),
+            "KeyH" => Some(Self::DistributeHorizontally),
+            "KeyV" => Some(Self::DistributeVertically),
+            _ => None,
+        }
+    }
+
+    // equal gaps need a block between the outer two
+    pub fn min_blocks(&self) -> usize {
+        match self {
+            Self::DistributeHorizontally | Self::DistributeVertically => 3,
+            _ => 2,
+        }
+    }
+
+    // how far every block moves, given the corners of the blocks
+    pub fn deltas(&self, corners: &[(DVec2, DVec2)]) -> Vec<DVec2> {
+        let left = corners.iter().map(|(top_left, _)| top_left.x).fold(f64::INFINITY, f64::min);
+        let top = corners.iter().map(|(top_left, _)| top_left.y).fold(f64::INFINITY, f64::min);
+        let right = corners.iter().map(|(_, bottom_right)| bottom_right.x).fold(f64::NEG_INFINITY, f64::max);
+        let bottom = corners.iter().map(|(_, bottom_right)| bottom_right.y).fold(f64::NEG_INFINITY, f64::max);
+        let center = (DVec2::new(left, top) + DVec2::new(right, bottom)) / 2.;
+        let shifts = match self {
+            Self::DistributeHorizontally => distributed(corners, |corner| corner.x),
+            Self::DistributeVertically => distributed(corners, |corner| corner.y),
+            _ => Vec::new(),
+        };
+        corners
+            .iter()
+            .enumerate()
+            .map(|(index, (top_left, bottom_right))| match self {
+                Self::AlignLeft => DVec2::new(left - top_left.x, 0.),
+                Self::AlignRight => DVec2::new(right - bottom_right.x, 0.),
+                Self::AlignTop => DVec2::new(0., top - top_left.y),
+                Self::AlignBottom => DVec2::new(0., bottom - bottom_right.y),
+                Self::AlignCenter => DVec2::new(center.x - (top_left.x + bottom_right.x) / 2., 0.),
+                Self::AlignMiddle => DVec2::new(0., center.y - (top_left.y + bottom_right.y) / 2.),
+                Self::DistributeHorizontally => DVec2::new(shifts[index], 0.),
+                Self::DistributeVertically => DVec2::new(0., shifts[index]),
+            })
+            .collect()
+    }
+}
+
+// shifts of the blocks along an axis so that the gaps between neighbouring blocks are equal, the outer blocks stay
+fn distributed(corners: &[(DVec2, DVec2)], axis: fn(DVec2) -> f64) -> Vec<f64> {
+    let mut order = (0..corners.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| {
+        let center = |i: usize| axis(corners[i].0) + axis(corners[i].1);
+        center(*a).total_cmp(&center(*b))
+    });
+    let first = axis(corners[order[0]].0);
+    let last = axis(corners[order[order.len() - 1]].1);
+    let sizes = corners.iter().map(|(top_left, bottom_right)| axis(*bottom_right) - axis(*top_left)).sum::<f64>();
+    let gap = (last - first - sizes) / (corners.len() - 1) as f64;
+    let mut shifts = vec![0.; corners.len()];
+    let mut start = first;
+    for i in order {
+        shifts[i] = start - axis(corners[i].0);
+        start += axis(corners[i].1) - axis(corners[i].0) + gap;
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // blocks of size 10 x 10 with the given top left corners
+    fn corners(top_lefts: &[(f64, f64)]) -> Vec<(DVec2, DVec2)> {
+        top_lefts.iter().map(|(x, y)| (DVec2::new(*x, *y), DVec2::new(x + 10., y + 10.))).collect()
+    }
+
+    #[test]
+    fn align_left_moves_to_the_leftmost_edge() {
+        let deltas = Arrangement::AlignLeft.deltas(&corners(&[(0., 0.), (30., 50.)]));
+        assert_eq!(deltas, [DVec2::ZERO, DVec2::new(-30., 0.)]);
+    }
+
+    #[test]
+    fn align_bottom_moves_to_the_lowest_edge() {
+        let deltas = Arrangement::AlignBottom.deltas(&corners(&[(0., 0.), (30., 50.)]));
+        assert_eq!(deltas, [DVec2::new(0., 50.), DVec2::ZERO]);
+    }
+
+    #[test]
+    fn align_center_uses_the_center_of_the_bounds() {
+        let deltas = Arrangement::AlignCenter.deltas(&corners(&[(0., 0.), (40., 20.)]));
+        assert_eq!(deltas, [DVec2::new(20., 0.), DVec2::new(-20., 0.)]);
+    }
+
+    #[test]
+    fn distribute_keeps_the_outer_blocks() {
+        // given out of order, the middle one is the last
+        let deltas = Arrangement::DistributeHorizontally.deltas(&corners(&[(0., 0.), (100., 5.), (20., 9.)]));
+        assert_eq!(deltas, [DVec2::ZERO, DVec2::ZERO, DVec2::new(30., 0.)]);
+    }
+
+    #[test]
+    fn distribute_vertically_evens_out_the_gaps() {
+        let deltas = Arrangement::DistributeVertically.deltas(&corners(&[(0., 0.), (0., 15.), (0., 60.), (0., 90.)]));
+        assert_eq!(deltas, [DVec2::ZERO, DVec2::new(0., 15.), DVec2::new(0., 0.), DVec2::ZERO]);
+    }
+}
@@ -10,6 +10,22 @@ pub enum StateStages {
     Finish(finish::State),
 }
 
+impl StateStages {
+    pub fn internal(&self) -> &internal::State {
+        match self {
+            StateStages::Start(state) => state.internal(),
+            StateStages::Finish(state) => state.internal(),
+        }
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        match self {
+            StateStages::Start(state) => state.internal_mut(),
+            StateStages::Finish(state) => state.internal_mut(),
+        }
+    }
+}
+
 impl Viewable<yew::Html> for StateStages {
     type Callback = yew::Callback<crate::editor::board::Event>;
 
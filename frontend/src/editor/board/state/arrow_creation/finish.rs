@@ -1,22 +1,50 @@
 use super::{block, internal, states::*};
+use crate::editor::board::graph;
 use crate::tools::viewable::Viewable;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct State {
     internal: internal::State,
+    sources: Vec<(block::Id, block::port::Index)>,
     end_block: block::Id,
+    // the free input ports are taken in order when the block itself was clicked
+    end_port: Option<block::port::Index>,
 }
 
 impl State {
-    pub fn from(internal: internal::State, end_block: block::Id) -> Self {
-        Self { internal, end_block }
+    pub fn from(internal: internal::State, sources: Vec<(block::Id, block::port::Index)>, end_block: block::Id, end_port: Option<block::port::Index>) -> Self {
+        Self { internal, sources, end_block, end_port }
+    }
+
+    pub fn internal(&self) -> &internal::State {
+        &self.internal
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.internal
     }
 
     pub fn commit(mut self) -> basic::State {
-        let end_block = self.end_block.clone();
-        self.internal
-            .iter_selected()
-            .for_each(|mut start| start.entry_mut().add_outgoing(end_block));
+        let end_block = self.end_block;
+        let end_ports = match self.end_port {
+            Some(port) => vec![port],
+            None => self.internal.free_input_ports(end_block),
+        };
+        if self.sources.len() > end_ports.len() {
+            log::info!("block {end_block} has no free input ports for {} arrows", self.sources.len() - end_ports.len());
+        }
+        let edges = self.sources
+            .iter()
+            .zip(end_ports)
+            .map(|((start, start_port), end_port)| graph::Edge::new(*start, *start_port, end_block, end_port))
+            .filter(|edge| self.internal.can_connect(edge))
+            .collect::<Vec<_>>();
+        if edges.is_empty() {
+            return basic::State::from(self.internal);
+        }
+        self.internal.checkpoint();
+        edges.into_iter().for_each(|edge| self.internal.connect(edge));
+        self.internal.graph_changed();
         basic::State::from(self.internal)
     }
 
@@ -2,19 +2,31 @@ use super::{block, internal, states::*};
 use crate::tools::viewable::Viewable;
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct State(internal::State);
+pub struct State {
+    internal: internal::State,
+    // output ports the arrows start from
+    sources: Vec<(block::Id, block::port::Index)>,
+}
 
 impl State {
-    pub(in crate::editor::board::state) fn from(internal: internal::State) -> Self {
-        Self(internal)
+    pub(in crate::editor::board::state) fn from(internal: internal::State, sources: Vec<(block::Id, block::port::Index)>) -> Self {
+        Self { internal, sources }
+    }
+
+    pub fn internal(&self) -> &internal::State {
+        &self.internal
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.internal
     }
 
     pub fn cancel(self) -> basic::State {
-        basic::State::from(self.0)
+        basic::State::from(self.internal)
     }
 
-    pub fn finish(self, end_block: block::Id) -> arrow_creation::finish::State {
-        arrow_creation::finish::State::from(self.0, end_block)
+    pub fn finish(self, end_block: block::Id, end_port: Option<block::port::Index>) -> arrow_creation::finish::State {
+        arrow_creation::finish::State::from(self.internal, self.sources, end_block, end_port)
     }
 
     pub fn to_states_enum(self) -> super::super::State {
@@ -26,6 +38,6 @@ impl Viewable<yew::Html> for State {
     type Callback = yew::Callback<crate::editor::board::Event>;
 
     fn view(&self, callback: Self::Callback) -> yew::Html {
-        self.0.html(callback)
+        self.internal.html(callback)
     }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ use crate::editor::board::block::state::StateInterface;
 use crate::tools::viewable::Viewable;
 
 use super::{block, internal, states::*};
+use crate::editor::board::graph;
 use block::vertex_data::VertexData;
 
 pub use predrag::SelectionModifier;
@@ -32,6 +33,14 @@ impl State {
         super::State::Basic(self)
     }
 
+    pub fn internal(&self) -> &internal::State {
+        &self.0
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.0
+    }
+
     pub fn hold_block(mut self, block_id: block::Id, modifier: SelectionModifier) -> HoldBlockResult {
         if !self.0.block_mut(block_id).selected() {
             if modifier == SelectionModifier::None {
@@ -48,38 +57,135 @@ impl State {
         }
     }
 
-    fn can_create_arrow(&self) -> bool {
-        self.0.graph().iter_vertices().any(|block| block::state::State::from(block).selected())
-    }
-    
-    fn create_arrow(self) -> arrow_creation::start::State {
-        arrow_creation::start::State::from(self.0)
+    // arrows from the first output port of every selected block
+    fn arrow_sources(&self) -> Vec<(block::Id, block::port::Index)> {
+        self.0.graph()
+            .iter_vertices()
+            .map(|block| block::state::State::from(block))
+            .filter(|block| block.selected() && !block.output_ports().is_empty())
+            .map(|block| (block.id(), 0))
+            .collect()
     }
+
     pub fn try_create_arrow(self) -> Result<arrow_creation::start::State, Self> {
-        if self.can_create_arrow() {
-            Ok(self.create_arrow())
+        let sources = self.arrow_sources();
+        if !sources.is_empty() {
+            Ok(arrow_creation::start::State::from(self.0, sources))
         } else {
-            log::info!("cannot create arrow: no selected blocks");
+            log::info!("cannot create arrow: no selected blocks with outputs");
             Err(self)
         }
     }
 
-    pub fn create_block(&mut self, pos: glam::DVec2) -> block::Id {
+    pub fn create_arrow_from_port(self, block_id: block::Id, port: block::port::Index) -> arrow_creation::start::State {
+        arrow_creation::start::State::from(self.0, vec![(block_id, port)])
+    }
+
+    pub fn create_block(&mut self, pos: glam::DVec2, kind: block::LayerKind, snap: bool) -> block::Id {
+        let pos = match snap {
+            true => super::State::snapped(pos),
+            false => pos,
+        };
+        self.0.checkpoint();
         self.0.clear_selection();
-        let mut entry = block::state::State::from(self.0.graph_mut().new_vertex(VertexData::from(pos)));
+        let mut entry = block::state::State::from(self.0.graph_mut().new_vertex(VertexData::from(pos, kind)));
         entry.set_selected(true);
-        entry.id()
+        let id = entry.id();
+        self.0.graph_changed();
+        id
     }
 
     pub fn start_rectangle_selection(self, start: glam::DVec2) -> rectangle_selection::State {
         rectangle_selection::State::from(self.0, start.clone(), start)
     }
 
-    pub fn remove_selected_blocks(&mut self) {
+    // removes the selected blocks with their arrows and the selected arrows
+    pub fn remove_selected(&mut self) {
         let selected = self.0.iter_selected().map(|block| block.id()).collect::<Vec<_>>();
+        let selected_edges = self.0.selected_edges();
+        if selected.is_empty() && selected_edges.is_empty() {
+            return;
+        }
+        self.0.checkpoint();
+        selected_edges.into_iter().for_each(|edge| {
+            self.0.graph_mut().remove_edge(edge);
+        });
         selected.into_iter().for_each(|block_id| {
             self.0.graph_mut().remove_vertex(block_id);
         });
+        self.0.graph_changed();
+    }
+
+    pub fn copy_selected(&self) -> common::project::Fragment {
+        self.0.copy_selected()
+    }
+
+    pub fn cut_selected(&mut self) -> common::project::Fragment {
+        let fragment = self.0.copy_selected();
+        self.remove_selected();
+        fragment
+    }
+
+    pub fn paste(&mut self, fragment: &common::project::Fragment, pos: glam::DVec2) {
+        self.0.paste(fragment, pos);
+    }
+
+    pub fn group_selected(&mut self) -> Result<(), String> {
+        self.0.group_selected()
+    }
+
+    pub fn ungroup_selected(&mut self) -> Result<(), String> {
+        self.0.ungroup_selected()
+    }
+
+    pub fn arrange_selected(&mut self, arrangement: super::arrangement::Arrangement) -> bool {
+        self.0.arrange_selected(arrangement)
+    }
+
+    pub fn layout(&mut self, selected_only: bool) -> bool {
+        self.0.layout(selected_only)
+    }
+
+    pub fn enter_group(&mut self, block_id: block::Id) -> bool {
+        self.0.enter_group(block_id)
+    }
+
+    pub fn exit_group(&mut self, levels: usize) {
+        self.0.exit_group(levels);
+    }
+
+    pub fn add_component(&mut self, component: &common::library::Component, pos: glam::DVec2) {
+        self.0.add_component(component, pos);
+    }
+
+    pub fn select_block(&mut self, block_id: block::Id) {
+        self.0.clear_selection();
+        self.0.block_mut(block_id).set_selected(true);
+    }
+
+    pub fn click_arrow(&mut self, edge: graph::Edge, modifier: SelectionModifier) {
+        let selected = self.0.graph().edge(edge).is_some_and(|data| data.selected);
+        match modifier {
+            SelectionModifier::None => {
+                self.0.clear_selection();
+                self.0.set_edge_selected(edge, true);
+            }
+            SelectionModifier::Add => self.0.set_edge_selected(edge, !selected),
+        }
+    }
+
+    pub fn set_param(&mut self, block_id: block::Id, name: &str, value: block::params::ParamValue) {
+        self.0.checkpoint();
+        self.0.block_mut(block_id).set_param(name, value);
+        self.0.graph_changed();
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.0.undo()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.0.redo()
     }
 
     pub fn clear_selection(&mut self) -> &mut Self {
@@ -1,26 +1,62 @@
+use std::rc::Rc;
+
 use crate::editor::board::block::state::StateInterface;
 use crate::tools::viewable::Viewable;
 
-use super::{internal, states::*};
+use super::{alignment, block, internal, states::*};
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct State(internal::State);
+pub struct State {
+    internal: internal::State,
+    // the whole drag is undone at once
+    before: Rc<internal::Graph>,
+    moved: bool,
+    // the block under the cursor, the other selected ones keep their distance to it
+    held: block::Id,
+    // where the held block would be without snapping and aligning
+    free_center: glam::DVec2,
+    guides: Vec<alignment::Guide>,
+}
 
 impl State {
-    pub fn from(internal: internal::State) -> Self {
-        Self(internal)
+    pub fn from(internal: internal::State, held: block::Id) -> Self {
+        let before = internal.snapshot();
+        let free_center = internal.block(held).center();
+        Self { internal, before, moved: false, held, free_center, guides: Vec::new() }
     }
 
     pub fn to_states_enum(self) -> super::State {
         super::State::DraggingBlocks(self)
     }
 
-    pub fn move_selected(mut self, delta: glam::DVec2) -> Self {
-        self.0.iter_selected().for_each(|mut block| block.move_block(delta));
+    pub fn internal(&self) -> &internal::State {
+        &self.internal
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.internal
+    }
+
+    // lining up with a block wins over the grid
+    pub fn move_selected(mut self, delta: glam::DVec2, snap: bool) -> Self {
+        self.free_center += delta;
+        let snapped = match snap {
+            true => super::State::snapped(self.free_center),
+            false => self.free_center,
+        };
+        let (x, y) = alignment::align(&self.internal, self.held, self.free_center);
+        let center = glam::DVec2::new(x.unwrap_or(snapped.x), y.unwrap_or(snapped.y));
+        let delta = center - self.internal.block(self.held).center();
+        self.internal.iter_selected().for_each(|mut block| block.move_block(delta));
+        self.moved |= delta != glam::DVec2::ZERO;
+        self.guides = alignment::guides(&self.internal, self.held);
         self
     }
-    pub fn stop(self) -> basic::State {
-        basic::State::from(self.0)
+    pub fn stop(mut self) -> basic::State {
+        if self.moved {
+            self.internal.record(self.before);
+        }
+        basic::State::from(self.internal)
     }
 }
 
@@ -28,7 +64,12 @@ impl Viewable<yew::Html> for State {
     type Callback = yew::Callback<crate::editor::board::Event>;
 
     fn view(&self, callback: Self::Callback) -> yew::Html {
-        self.0.html(callback)
+        yew::html! {
+            <>
+                {self.internal.html(callback)}
+                {for self.guides.iter().map(alignment::Guide::html)}
+            </>
+        }
     }
 }
 
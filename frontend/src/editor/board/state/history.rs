@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use super::internal::Graph;
+
+pub const DEFAULT_DEPTH: usize = 100;
+
+// snapshots of the board graph before each edit, shared between cloned states
+#[derive(Debug, PartialEq, Clone)]
+pub struct History {
+    undo: VecDeque<Rc<Graph>>,
+    redo: Vec<Rc<Graph>>,
+    // the oldest snapshots are dropped above it
+    depth: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self { undo: VecDeque::new(), redo: Vec::new(), depth: DEFAULT_DEPTH }
+    }
+}
+
+impl History {
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    // a new edit makes the undone ones unreachable
+    pub fn push(&mut self, before: Rc<Graph>) {
+        self.undo.push_back(before);
+        self.redo.clear();
+        self.trim();
+    }
+
+    pub fn undo(&mut self, current: Rc<Graph>) -> Option<Rc<Graph>> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: Rc<Graph>) -> Option<Rc<Graph>> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.trim();
+        Some(next)
+    }
+}
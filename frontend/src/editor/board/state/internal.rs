@@ -1,34 +1,681 @@
-use crate::editor::board::block::{self, state::StateInterface};
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
 
-pub type Graph = super::super::graph::Graph<block::vertex_data::VertexData>;
+use common::project;
+
+use crate::editor::board::{graph, validation};
+use crate::editor::board::block::{self, shape::{self, InferredShape}, state::StateInterface, vertex_data::VertexData};
+
+pub type Graph = super::super::graph::Graph<block::vertex_data::VertexData, crate::editor::board::arrow::EdgeData>;
+
+// distance from the grouped blocks to the boundary blocks made for them
+const GROUP_MARGIN: f64 = 250.0;
+const GROUP_INPUT_SPACING: f64 = 200.0;
+
+// a level of the board the editor went into a group from
+#[derive(Debug, PartialEq, Clone)]
+struct Parent {
+    graph: Graph,
+    block: block::Id,
+    history: super::history::History,
+    // the group has to be written back on the way out
+    edited: bool,
+    // shapes arriving at the ports of the group, the GroupInput blocks inside pass them on
+    inputs: Vec<Option<shape::Shape>>,
+}
+
+// shape of a group block on the edited level, kept while its contents and the shapes coming in stay the same
+#[derive(Debug, PartialEq, Clone)]
+struct GroupShape {
+    group: Rc<Graph>,
+    inputs: Vec<shape::Shape>,
+    shape: InferredShape,
+}
 
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct State {
-    graph: Graph
+    // graph of the level being edited, the root one or the inside of a group
+    graph: Graph,
+    history: super::history::History,
+    // found on every edit, so the panel does not walk the graph on each render
+    problems: Vec<validation::Problem>,
+    parents: Vec<Parent>,
+    // blocks moving to the places the layout found for them
+    animation: Option<super::animation::Animation>,
+    group_shapes: BTreeMap<block::Id, GroupShape>,
 }
 
 impl State {
-    #[allow(unused)]
-    pub fn from(graph: Graph) -> Self { Self { graph } }
+    pub fn from(graph: Graph) -> Self {
+        let problems = validation::validate(&graph, false);
+        Self { graph, history: Default::default(), problems, parents: Vec::new(), animation: None, group_shapes: BTreeMap::new() }
+    }
+
+    pub fn problems(&self) -> &[validation::Problem] { &self.problems }
 
     pub fn graph(&self) -> &Graph { &self.graph }
     pub fn graph_mut(&mut self) -> &mut Graph { &mut self.graph }
 
+    pub fn snapshot(&self) -> Rc<Graph> { Rc::new(self.graph.clone()) }
+
+    // remembers the graph as it was before an edit
+    pub fn record(&mut self, before: Rc<Graph>) {
+        self.history.push(before);
+        self.mark_edited();
+    }
+
+    fn mark_edited(&mut self) {
+        if let Some(parent) = self.parents.last_mut() {
+            parent.edited = true;
+        }
+    }
+
+    pub fn checkpoint(&mut self) {
+        self.record(self.snapshot());
+    }
+
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history.set_depth(depth);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let current = self.snapshot();
+        self.history.undo(current).map(|graph| self.restore(graph)).is_some()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let current = self.snapshot();
+        self.history.redo(current).map(|graph| self.restore(graph)).is_some()
+    }
+
+    fn restore(&mut self, graph: Rc<Graph>) {
+        self.graph = Rc::unwrap_or_clone(graph);
+        self.graph_changed();
+    }
+
+    // ids of the groups the edited level is nested in, outermost first
+    pub fn path(&self) -> Vec<block::Id> {
+        self.parents.iter().map(|parent| parent.block).collect()
+    }
+
+    // continues editing inside of a group block, with an undo history of its own
+    pub fn enter_group(&mut self, id: block::Id) -> bool {
+        let Some(group) = self.block(id).group() else {
+            return false;
+        };
+        self.clear_selection();
+        let inputs = self.input_shapes(id);
+        let mut history = super::history::History::default();
+        history.set_depth(self.history.depth());
+        let graph = std::mem::replace(&mut self.graph, Rc::unwrap_or_clone(group));
+        let history = std::mem::replace(&mut self.history, history);
+        self.parents.push(Parent { graph, block: id, history, edited: false, inputs });
+        self.group_shapes.clear();
+        self.graph_changed();
+        true
+    }
+
+    // goes the given number of levels up, the edits made inside are undone as a whole
+    pub fn exit_group(&mut self, levels: usize) {
+        for _ in 0..levels {
+            let Some(parent) = self.parents.pop() else {
+                break;
+            };
+            self.clear_selection();
+            let inner = std::mem::replace(&mut self.graph, parent.graph);
+            self.history = parent.history;
+            if parent.edited {
+                self.checkpoint();
+                self.block_mut(parent.block).set_group(Rc::new(inner));
+            }
+        }
+        self.group_shapes.clear();
+        self.graph_changed();
+    }
+
+    // the whole board with the edited level written into the groups above it
+    fn root_graph(&self) -> Graph {
+        self.parents.iter().rev().fold(self.graph.clone(), |inner, parent| {
+            let graph = parent.graph.clone();
+            block::state::State::from(graph.entry(parent.block).unwrap()).set_group(Rc::new(inner));
+            graph
+        })
+    }
+
+    fn block_ids(&self) -> Vec<block::Id> {
+        self.graph().iter_vertices().map(|entry| entry.id()).collect()
+    }
+
+    fn blocks_of_kind(graph: &Graph, kind: block::LayerKind) -> Vec<block::Id> {
+        graph
+            .iter_vertices()
+            .map(block::state::State::from)
+            .filter(|block| block.kind() == kind)
+            .map(|block| block.id())
+            .collect()
+    }
+
+    // where the values of a block are in the flattened board, a group shows the ones of its GroupOutput
+    fn flat_path(&self, id: block::Id) -> Vec<block::Id> {
+        let mut path = self.path();
+        path.push(id);
+        let outputs = self.block(id).group().map(|group| Self::blocks_of_kind(&group, block::LayerKind::GroupOutput));
+        if let Some([output]) = outputs.as_deref() {
+            path.push(*output);
+        }
+        path
+    }
+
     pub fn block_mut(&mut self, id: block::Id) -> block::state::State<'_> {
         block::state::State::from(self.graph_mut().entry(id).unwrap())
     }
 
+    pub fn block(&self, id: block::Id) -> block::state::State<'_> {
+        block::state::State::from(self.graph().entry(id).unwrap())
+    }
+
     pub fn iter_blocks(&mut self) -> impl Iterator<Item = block::state::State<'_>> {
         self.graph_mut().iter_vertices()
             .map(|block| block::state::State::from(block))
     }
 
-    pub fn iter_selected(&mut self) -> impl Iterator<Item = block::state::State<'_>> {
-        self.iter_blocks().filter(|block| block.selected())
+    pub fn iter_selected(&self) -> impl Iterator<Item = block::state::State<'_>> {
+        self.graph().iter_vertices().map(block::state::State::from).filter(|block| block.selected())
+    }
+
+    // the only selected block, if exactly one is selected
+    pub fn single_selected(&self) -> Option<block::StateDump> {
+        let mut selected = self.iter_selected();
+        match (selected.next(), selected.next()) {
+            (Some(block), None) => Some(block.dump()),
+            _ => None,
+        }
+    }
+
+    // box around all blocks or the selected ones, None when there are none
+    pub fn bounds(&self, selected_only: bool) -> Option<(glam::DVec2, glam::DVec2)> {
+        self.blocks(selected_only)
+            .map(|block| (block.top_left(), block.bottom_right()))
+            .reduce(|(top_left, bottom_right), (other_top_left, other_bottom_right)| {
+                (top_left.min(other_top_left), bottom_right.max(other_bottom_right))
+            })
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.iter_selected().count()
+    }
+
+    // every block or only the selected ones
+    fn blocks(&self, selected_only: bool) -> Box<dyn Iterator<Item = block::state::State<'_>> + '_> {
+        match selected_only {
+            true => Box::new(self.iter_selected()),
+            false => Box::new(self.graph().iter_vertices().map(block::state::State::from)),
+        }
     }
 
     pub fn clear_selection(&mut self) {
         self.iter_selected().for_each(|mut block| block.set_selected(false));
+        self.graph_mut().iter_mut_edges_data().for_each(|(_, data)| data.selected = false);
+    }
+
+    pub fn selected_edges(&self) -> Vec<graph::Edge> {
+        self.graph().iter_edges().filter(|edge| self.graph().edge(*edge).is_some_and(|data| data.selected)).collect()
+    }
+
+    pub fn free_input_ports(&self, id: block::Id) -> Vec<block::port::Index> {
+        let taken = self.graph().incoming_edges(id).into_iter().map(|edge| edge.to_port).collect::<BTreeSet<_>>();
+        (0..self.block(id).input_ports().len()).filter(|port| !taken.contains(port)).collect()
+    }
+
+    pub fn set_edge_selected(&mut self, edge: graph::Edge, selected: bool) {
+        if let Some(data) = self.graph_mut().edge_mut(edge) {
+            data.selected = selected;
+        }
+    }
+
+    // refreshes everything derived from the graph after it was edited
+    pub fn graph_changed(&mut self) {
+        // the animated blocks may be gone or belong to another level
+        self.animation = None;
+        self.update_shapes();
+        self.problems = validation::validate(self.graph(), !self.parents.is_empty());
+        self.iter_blocks().for_each(|mut block| block.set_run_result(None));
+    }
+
+    pub fn set_run_results(&mut self, report: common::api::ForwardReport) {
+        let mut stats = report.blocks.into_iter().map(|stats| (stats.path.clone(), stats)).collect::<BTreeMap<_, _>>();
+        self.block_ids().into_iter().for_each(|id| {
+            let run_result = stats.remove(&self.flat_path(id)).map(block::RunResult::Stats);
+            self.block_mut(id).set_run_result(run_result);
+        });
+    }
+
+    // the backend reports errors at the blocks of the root level
+    pub fn set_run_error(&mut self, error: &common::api::Error) {
+        let root = self.parents.is_empty();
+        self.iter_blocks().for_each(|mut block| {
+            let run_result = match root && error.block == Some(block.id()) {
+                true => Some(block::RunResult::Failed(error.message.clone())),
+                false => None,
+            };
+            block.set_run_result(run_result);
+        });
+    }
+
+    // known shapes arriving at the input ports of a block, in port order
+    fn input_shapes(&self, id: block::Id) -> Vec<Option<shape::Shape>> {
+        let edges = self.graph().incoming_edges(id);
+        (0..self.block(id).input_ports().len())
+            .map(|port| match edges.iter().find(|edge| edge.to_port == port).map(|edge| self.block(edge.from).shape()) {
+                Some(InferredShape::Known(shape)) => Some(shape),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // what comes out of a group, its contents are only flattened again when they or the shapes coming in change
+    fn group_shape(&mut self, id: block::Id, inputs: Vec<shape::Shape>) -> InferredShape {
+        let Some(group) = self.block(id).group() else {
+            return InferredShape::Unknown;
+        };
+        if let Some(cached) = self.group_shapes.get(&id).filter(|cached| Rc::ptr_eq(&cached.group, &group) && cached.inputs == inputs) {
+            return cached.shape.clone();
+        }
+        let known = inputs.iter().cloned().map(Some).collect::<Vec<_>>();
+        let shape = match Self::export(&group).group_output_shape(&known) {
+            Some(shape) => InferredShape::Known(shape),
+            None => InferredShape::Unknown,
+        };
+        self.group_shapes.insert(id, GroupShape { group, inputs, shape: shape.clone() });
+        shape
+    }
+
+    // propagates tensor shapes from Input blocks along the arrows of the edited level,
+    // GroupInput blocks take the ones arriving at the group from the level above
+    fn update_shapes(&mut self) {
+        let order = self.graph().topological_order();
+        let ordered = order.iter().copied().collect::<BTreeSet<_>>();
+        // blocks on cycles are never reached
+        self.iter_blocks()
+            .filter(|block| !ordered.contains(&block.id()))
+            .for_each(|mut block| block.set_shape(InferredShape::Unknown));
+        self.group_shapes.retain(|id, _| ordered.contains(id));
+        let boundary = Self::blocks_of_kind(self.graph(), block::LayerKind::GroupInput)
+            .into_iter()
+            .zip(self.parents.last().map(|parent| parent.inputs.clone()).unwrap_or_default())
+            .collect::<BTreeMap<_, _>>();
+        order.into_iter().for_each(|id| {
+            let inputs = self.graph()
+                .incoming_edges(id)
+                .into_iter()
+                .map(|edge| match self.block(edge.from).shape() {
+                    InferredShape::Known(shape) => Some((edge.from, shape)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            let kind = self.block(id).kind();
+            let ports = self.block(id).input_ports().len();
+            // waits until every input port is connected
+            let shape = match (kind, inputs) {
+                (block::LayerKind::GroupInput, _) => match boundary.get(&id).cloned().flatten() {
+                    Some(shape) => InferredShape::Known(shape),
+                    None => InferredShape::Unknown,
+                },
+                (block::LayerKind::Group, Some(inputs)) if inputs.len() == ports => {
+                    self.group_shape(id, inputs.into_iter().map(|(_, shape)| shape).collect())
+                }
+                (_, Some(inputs)) if inputs.len() == ports => {
+                    match shape::infer(kind, &self.block(id).params(), &inputs) {
+                        Ok(shape) => InferredShape::Known(shape),
+                        Err(error) => InferredShape::Mismatch(error),
+                    }
+                }
+                _ => InferredShape::Unknown,
+            };
+            self.block_mut(id).set_shape(shape);
+        });
+    }
+
+    fn export(graph: &Graph) -> common::graph::Graph {
+        common::graph::Graph {
+            vertices: graph
+                .iter_vertices()
+                .map(|entry| {
+                    let block = block::state::State::from(entry);
+                    let group = block.group().map(|group| Box::new(Self::export(&group)));
+                    common::graph::Vertex { id: block.id(), kind: block.kind(), params: block.params(), group }
+                })
+                .collect(),
+            edges: graph.iter_edges().collect(),
+        }
+    }
+
+    // the graph in the form understood by the backend, always the whole board
+    pub fn export_graph(&self) -> common::graph::Graph {
+        Self::export(&self.root_graph())
+    }
+
+    fn project_block(block: block::state::State) -> project::Block {
+        let center = block.center();
+        let group = block.group().map(|group| Self::project_group(&group));
+        let component = block.component();
+        project::Block { id: block.id(), kind: block.kind(), params: block.params(), x: center.x, y: center.y, group, component }
+    }
+
+    fn project_group(graph: &Graph) -> project::Group {
+        project::Group { last_id: graph.last_id(), blocks: Self::project_blocks(graph), edges: graph.iter_edges().collect() }
+    }
+
+    fn project_blocks(graph: &Graph) -> Vec<project::Block> {
+        graph
+            .iter_vertices()
+            .map(|entry| Self::project_block(block::state::State::from(entry)))
+            .collect()
+    }
+
+    fn block_data(block: &project::Block, offset: glam::DVec2) -> VertexData {
+        let group = block.group.as_ref().map(|group| Rc::new(Self::project_graph(group.last_id, &group.blocks, &group.edges)));
+        VertexData::from(glam::DVec2::new(block.x, block.y) + offset, block.kind)
+            .with_params(block.params.clone())
+            .with_group(group)
+            .with_component(block.component.clone())
+    }
+
+    fn project_graph(last_id: block::Id, blocks: &[project::Block], edges: &[graph::Edge]) -> Graph {
+        let mut graph = Graph::with_last_id(last_id);
+        blocks.iter().for_each(|block| {
+            graph.insert_vertex(block.id, Self::block_data(block, glam::DVec2::ZERO));
+        });
+        edges.iter().for_each(|edge| graph.add_edge(*edge));
+        graph
+    }
+
+    pub fn to_project(&self, viewbox: project::Viewbox) -> project::Project {
+        let root = self.root_graph();
+        project::Project {
+            version: project::VERSION,
+            last_id: root.last_id(),
+            viewbox,
+            blocks: Self::project_blocks(&root),
+            edges: root.iter_edges().collect(),
+        }
+    }
+
+    // the project is expected to be validated by the backend
+    pub fn from_project(project: &project::Project) -> Self {
+        let mut state = Self::from(Self::project_graph(project.last_id, &project.blocks, &project.edges));
+        state.graph_changed();
+        state
+    }
+
+    // the selected blocks and the arrows between them
+    pub fn copy_selected(&self) -> project::Fragment {
+        let blocks = self.iter_selected()
+            .map(Self::project_block)
+            .collect::<Vec<_>>();
+        let ids = blocks.iter().map(|block| block.id).collect::<BTreeSet<_>>();
+        let edges = self.graph()
+            .iter_edges()
+            .filter(|edge| ids.contains(&edge.from) && ids.contains(&edge.to))
+            .collect();
+        project::Fragment { blocks, edges }
+    }
+
+    // the network has to stay acyclic
+    pub fn can_connect(&self, edge: &graph::Edge) -> bool {
+        let cycle = self.graph().is_reachable(edge.to, edge.from);
+        if cycle {
+            log::info!("arrow from block {} to block {} would create a cycle", edge.from, edge.to);
+        }
+        !cycle
+    }
+
+    // an input port takes a single arrow, the new one replaces the old
+    pub fn connect(&mut self, edge: graph::Edge) {
+        let replaced = self.graph().incoming_edges(edge.to).into_iter().filter(|old| old.to_port == edge.to_port).collect::<Vec<_>>();
+        replaced.into_iter().for_each(|old| { self.graph_mut().remove_edge(old); });
+        self.graph_mut().add_edge(edge);
+    }
+
+    // adds the fragment under fresh ids centered at the given point, the pasted blocks get selected
+    pub fn paste(&mut self, fragment: &project::Fragment, center: glam::DVec2) {
+        if fragment.blocks.is_empty() {
+            return;
+        }
+        self.checkpoint();
+        self.clear_selection();
+        let count = fragment.blocks.len() as f64;
+        let fragment_center = fragment.blocks.iter().map(|block| glam::DVec2::new(block.x, block.y)).sum::<glam::DVec2>() / count;
+        let ids = fragment.blocks
+            .iter()
+            .map(|block| {
+                let data = Self::block_data(block, center - fragment_center);
+                let mut pasted = block::state::State::from(self.graph_mut().new_vertex(data));
+                pasted.set_selected(true);
+                (block.id, pasted.id())
+            })
+            .collect::<BTreeMap<_, _>>();
+        // the payload may come from outside, arrows to unknown blocks or ports are dropped,
+        // arrows closing a cycle too, and of several arrows into one port the last one stays
+        let edges = fragment.edges
+            .iter()
+            .filter_map(|edge| {
+                let (from, to) = (*ids.get(&edge.from)?, *ids.get(&edge.to)?);
+                let valid = edge.from_port < self.block(from).output_ports().len() && edge.to_port < self.block(to).input_ports().len();
+                valid.then(|| graph::Edge::new(from, edge.from_port, to, edge.to_port))
+            })
+            .collect::<BTreeSet<_>>();
+        for edge in edges {
+            if self.can_connect(&edge) {
+                self.connect(edge);
+            }
+        }
+        self.graph_changed();
+    }
+
+    // a group block holding an instance of the library component
+    pub fn add_component(&mut self, component: &common::library::Component, pos: glam::DVec2) {
+        self.checkpoint();
+        self.clear_selection();
+        let group = &component.group;
+        let data = VertexData::from(pos, block::LayerKind::Group)
+            .with_group(Some(Rc::new(Self::project_graph(group.last_id, &group.blocks, &group.edges))))
+            .with_component(Some(component.reference()));
+        block::state::State::from(self.graph_mut().new_vertex(data)).set_selected(true);
+        self.graph_changed();
+    }
+
+    // the only selected block with its contents, if it is a group
+    pub fn selected_group(&self) -> Option<(block::Id, Option<common::library::ComponentRef>, project::Group)> {
+        let block = self.single_selected()?;
+        let group = block.group().filter(|_| block.kind() == block::LayerKind::Group)?;
+        Some((block.id(), block.component(), Self::project_group(&group)))
+    }
+
+    // the group was saved to the library, it is now an instance of the saved version
+    pub fn set_component(&mut self, id: block::Id, component: common::library::ComponentRef) {
+        if let Some(entry) = self.graph().entry(id) {
+            block::state::State::from(entry).set_component(Some(component));
+            self.mark_edited();
+        }
+    }
+
+    // lines up or spaces out the selected blocks as one edit, false when there are too few of them or nothing moves
+    pub fn arrange_selected(&mut self, arrangement: super::arrangement::Arrangement) -> bool {
+        let selected = self.iter_selected()
+            .map(|block| (block.id(), (block.top_left(), block.bottom_right())))
+            .collect::<Vec<_>>();
+        if selected.len() < arrangement.min_blocks() {
+            return false;
+        }
+        let corners = selected.iter().map(|(_, corners)| *corners).collect::<Vec<_>>();
+        let deltas = arrangement.deltas(&corners);
+        if deltas.iter().all(|delta| *delta == glam::DVec2::ZERO) {
+            return false;
+        }
+        self.checkpoint();
+        for ((id, _), delta) in selected.iter().zip(deltas) {
+            self.block_mut(*id).move_block(delta);
+        }
+        true
+    }
+
+    // lays the blocks out in columns along the arrows, only the selected ones if asked;
+    // the blocks get there over the frames of `animate`, the laid out graph is centered where the blocks were
+    pub fn layout(&mut self, selected_only: bool) -> bool {
+        let from = self.blocks(selected_only)
+            .map(|block| (block.id(), block.center()))
+            .collect::<BTreeMap<_, _>>();
+        if from.len() < 2 {
+            return false;
+        }
+        let ids = from.keys().copied().collect::<Vec<_>>();
+        let centers = common::layout::layered(&ids, &self.graph().iter_edges().collect::<Vec<_>>());
+        let centers = centers.into_iter().map(|(id, (x, y))| (id, glam::DVec2::new(x, y))).collect::<BTreeMap<_, _>>();
+        let count = from.len() as f64;
+        let shift = from.values().sum::<glam::DVec2>() / count - centers.values().sum::<glam::DVec2>() / count;
+        let to = centers.into_iter().map(|(id, center)| (id, center + shift)).collect();
+        self.checkpoint();
+        self.animation = Some(super::animation::Animation::new(from, to));
+        true
+    }
+
+    // moves the laid out blocks a frame further, false once they arrived
+    pub fn animate(&mut self) -> bool {
+        let Some(centers) = self.animation.as_mut().and_then(super::animation::Animation::next) else {
+            self.animation = None;
+            return false;
+        };
+        for (id, center) in centers {
+            if let Some(entry) = self.graph().entry(id) {
+                let mut block = block::state::State::from(entry);
+                let delta = center - block.center();
+                block.move_block(delta);
+            }
+        }
+        true
+    }
+
+    // replaces the selected blocks with a group, arrows crossing the selection go through boundary blocks
+    pub fn group_selected(&mut self) -> Result<(), String> {
+        let selected = self.iter_selected()
+            .map(|block| block.id())
+            .collect::<BTreeSet<_>>();
+        if selected.is_empty() {
+            return Err("no blocks are selected".to_string());
+        }
+        if let Some(kind) = selected.iter().map(|id| self.block(*id).kind()).find(|kind| matches!(kind, block::LayerKind::Input | block::LayerKind::Output)) {
+            return Err(format!("{kind} blocks cannot be grouped"));
+        }
+        let edges = self.graph().iter_edges().collect::<Vec<_>>();
+        let inside = |id: block::Id| selected.contains(&id);
+        // every outside output read by the selection gets a GroupInput
+        let sources = edges.iter().filter(|edge| !inside(edge.from) && inside(edge.to)).map(|edge| (edge.from, edge.from_port)).collect::<BTreeSet<_>>();
+        let outputs = edges.iter().filter(|edge| inside(edge.from) && !inside(edge.to)).map(|edge| (edge.from, edge.from_port)).collect::<BTreeSet<_>>();
+        if outputs.len() > 1 {
+            return Err("a group has a single output, but the blocks around read several outputs of the selection".to_string());
+        }
+
+        let centers = selected.iter().map(|id| self.block(*id).center()).collect::<Vec<_>>();
+        let center = centers.iter().sum::<glam::DVec2>() / centers.len() as f64;
+        let left = centers.iter().map(|center| center.x).fold(f64::INFINITY, f64::min) - GROUP_MARGIN;
+        let right = centers.iter().map(|center| center.x).fold(f64::NEG_INFINITY, f64::max) + GROUP_MARGIN;
+        // the blocks keep their ids inside, boundary blocks continue after them
+        let mut group = Graph::with_last_id(self.graph().last_id());
+        selected.iter().for_each(|id| {
+            let data = self.graph().entry(*id).unwrap().data().clone();
+            let mut block = block::state::State::from(group.insert_vertex(*id, data).unwrap());
+            block.set_selected(false);
+            block.set_run_result(None);
+        });
+        edges.iter().filter(|edge| inside(edge.from) && inside(edge.to)).for_each(|edge| group.add_edge(*edge));
+        sources.iter().enumerate().for_each(|(index, (from, from_port))| {
+            let offset = (index as f64 - (sources.len() - 1) as f64 / 2.) * GROUP_INPUT_SPACING;
+            let input = group.new_vertex(VertexData::from(glam::DVec2::new(left, center.y + offset), block::LayerKind::GroupInput)).id();
+            edges.iter()
+                .filter(|edge| edge.from == *from && edge.from_port == *from_port && inside(edge.to))
+                .for_each(|edge| group.add_edge(graph::Edge::new(input, 0, edge.to, edge.to_port)));
+        });
+        let output = group.new_vertex(VertexData::from(glam::DVec2::new(right, center.y), block::LayerKind::GroupOutput)).id();
+        if let Some((from, from_port)) = outputs.first() {
+            group.add_edge(graph::Edge::new(*from, *from_port, output, 0));
+        }
+
+        self.checkpoint();
+        self.clear_selection();
+        selected.iter().for_each(|id| self.graph_mut().remove_vertex(*id));
+        let data = VertexData::from(center, block::LayerKind::Group).with_group(Some(Rc::new(group)));
+        let mut group_block = block::state::State::from(self.graph_mut().new_vertex(data));
+        group_block.set_selected(true);
+        let id = group_block.id();
+        sources.iter().enumerate().for_each(|(index, (from, from_port))| {
+            self.graph_mut().add_edge(graph::Edge::new(*from, *from_port, id, index));
+        });
+        edges.iter()
+            .filter(|edge| inside(edge.from) && !inside(edge.to))
+            .for_each(|edge| self.graph_mut().add_edge(graph::Edge::new(id, 0, edge.to, edge.to_port)));
+        self.graph_changed();
+        Ok(())
+    }
+
+    // puts the contents of the selected group back on this level under fresh ids
+    pub fn ungroup_selected(&mut self) -> Result<(), String> {
+        let Some(selected) = self.single_selected() else {
+            return Err("select a single group".to_string());
+        };
+        let Some(group) = selected.group().filter(|_| selected.kind() == block::LayerKind::Group) else {
+            return Err(format!("{} is not a group", selected.kind()));
+        };
+        let id = selected.id();
+        let inputs = Self::blocks_of_kind(&group, block::LayerKind::GroupInput);
+        let outputs = Self::blocks_of_kind(&group, block::LayerKind::GroupOutput);
+        let edges = self.graph().iter_edges().collect::<Vec<_>>();
+        let sources = edges.iter().filter(|edge| edge.to == id).map(|edge| (edge.to_port, (edge.from, edge.from_port))).collect::<BTreeMap<_, _>>();
+        let targets = edges.iter().filter(|edge| edge.from == id).map(|edge| (edge.to, edge.to_port)).collect::<Vec<_>>();
+        let blocks = group
+            .iter_vertices()
+            .map(block::state::State::from)
+            .filter(|block| !block.kind().is_group_boundary())
+            .map(|block| (block.id(), block.center()))
+            .collect::<Vec<_>>();
+
+        self.checkpoint();
+        self.clear_selection();
+        self.graph_mut().remove_vertex(id);
+        let offset = match blocks.is_empty() {
+            true => glam::DVec2::ZERO,
+            false => selected.center() - blocks.iter().map(|(_, center)| *center).sum::<glam::DVec2>() / blocks.len() as f64,
+        };
+        let ids = blocks
+            .iter()
+            .map(|(inner, _)| {
+                let data = group.entry(*inner).unwrap().data().clone();
+                let mut block = block::state::State::from(self.graph_mut().new_vertex(data));
+                block.move_block(offset);
+                block.set_selected(true);
+                (*inner, block.id())
+            })
+            .collect::<BTreeMap<_, _>>();
+        // an output inside as seen from this level, GroupInput blocks lead to the arrows into the group
+        let resolve = |from: block::Id, from_port: block::port::Index| match inputs.iter().position(|input| *input == from) {
+            Some(index) => sources.get(&index).copied(),
+            None => ids.get(&from).map(|id| (*id, from_port)),
+        };
+        let mut new_edges = BTreeSet::new();
+        group.iter_edges().for_each(|edge| match (resolve(edge.from, edge.from_port), ids.get(&edge.to)) {
+            (Some((from, from_port)), Some(to)) => {
+                new_edges.insert(graph::Edge::new(from, from_port, *to, edge.to_port));
+            }
+            (Some((from, from_port)), None) if outputs.contains(&edge.to) => {
+                targets.iter().for_each(|(to, to_port)| {
+                    new_edges.insert(graph::Edge::new(from, from_port, *to, *to_port));
+                });
+            }
+            _ => {}
+        });
+        new_edges.into_iter().for_each(|edge| self.graph_mut().add_edge(edge));
+        self.graph_changed();
+        Ok(())
     }
 
     pub fn blocks_html(&self, callback: yew::Callback<crate::editor::board::Event>) -> yew::Html {
@@ -40,12 +687,12 @@ impl State {
         }).collect::<yew::Html>()
     }
 
-    pub fn arrows_html(&self) -> yew::Html {
+    pub fn arrows_html(&self, callback: yew::Callback<crate::editor::board::Event>) -> yew::Html {
         self.graph()
             .iter_edges()
             .map(|edge|
                 crate::editor::board::arrow::Arrow::from(edge, self.graph())
-                    .html()
+                    .html(callback.reform(crate::editor::board::Event::ArrowEvent))
             )
             .collect::<yew::Html>()
     }
@@ -53,7 +700,7 @@ impl State {
     pub fn html(&self, callback: yew::Callback<crate::editor::board::Event>) -> yew::Html {
         yew::html!{
             <>
-                {self.arrows_html()}
+                {self.arrows_html(callback.clone())}
                 {self.blocks_html(callback)}
             </>
         }
@@ -25,6 +25,14 @@ impl State {
         super::State::PredragBlocks(self)
     }
 
+    pub fn internal(&self) -> &internal::State {
+        &self.internal
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.internal
+    }
+
     pub fn deselect(mut self) -> basic::State {
         match self.selection_modifier {
             SelectionModifier::Add => {
@@ -39,7 +47,7 @@ impl State {
     }
 
     pub fn drag_blocks(self) -> dragging_blocks::State {
-        dragging_blocks::State::from(self.internal)
+        dragging_blocks::State::from(self.internal, self.clicked_block)
     }
 }
 
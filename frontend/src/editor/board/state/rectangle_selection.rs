@@ -21,6 +21,14 @@ impl State {
         super::State::RectangleSelection(self)
     }
 
+    pub fn internal(&self) -> &internal::State {
+        &self.internal
+    }
+
+    pub fn internal_mut(&mut self) -> &mut internal::State {
+        &mut self.internal
+    }
+
     pub fn move_end(mut self, new_value: glam::DVec2) -> Self {
         self.end = new_value;
         self
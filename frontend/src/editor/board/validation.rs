@@ -0,0 +1,79 @@
+use super::block::{self, state::StateInterface};
+use super::state::internal::Graph;
+
+// something that keeps the board from being built into a network
+#[derive(PartialEq, Debug, Clone)]
+pub struct Problem {
+    pub block: Option<block::Id>,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(block: Option<block::Id>, message: String) -> Self {
+        Self { block, message }
+    }
+}
+
+fn single_kind(graph: &Graph, kind: block::LayerKind, problems: &mut Vec<Problem>) {
+    let blocks = graph
+        .iter_vertices()
+        .map(block::state::State::from)
+        .filter(|block| block.kind() == kind)
+        .map(|block| block.id())
+        .collect::<Vec<_>>();
+    match blocks.as_slice() {
+        [] => problems.push(Problem::new(None, format!("no {kind} block"))),
+        [_] => {}
+        [_, extra @ ..] => problems.extend(extra.iter().map(|id| Problem::new(Some(*id), format!("{kind} #{id} is not the only {kind} block")))),
+    }
+}
+
+// blocks that cannot be on this level of nesting
+fn misplaced(graph: &Graph, kinds: &[block::LayerKind], place: &str, problems: &mut Vec<Problem>) {
+    graph.iter_vertices().map(block::state::State::from).filter(|block| kinds.contains(&block.kind())).for_each(|block| {
+        let (id, kind) = (block.id(), block.kind());
+        problems.push(Problem::new(Some(id), format!("{kind} #{id} cannot be {place}")));
+    });
+}
+
+// `nested` is set for the graph inside of a group
+pub fn validate(graph: &Graph, nested: bool) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if graph.iter_vertices().next().is_none() {
+        return problems;
+    }
+    match nested {
+        false => {
+            single_kind(graph, block::LayerKind::Input, &mut problems);
+            single_kind(graph, block::LayerKind::Output, &mut problems);
+            misplaced(graph, &[block::LayerKind::GroupInput, block::LayerKind::GroupOutput], "outside of a group", &mut problems);
+        }
+        true => {
+            single_kind(graph, block::LayerKind::GroupOutput, &mut problems);
+            misplaced(graph, &[block::LayerKind::Input, block::LayerKind::Output], "inside of a group", &mut problems);
+        }
+    }
+    if let Some(cycle) = graph.find_cycle() {
+        let path = cycle.iter().chain(cycle.first()).map(|id| format!("#{id}")).collect::<Vec<_>>().join(" -> ");
+        problems.push(Problem::new(cycle.first().copied(), format!("cycle {path}")));
+    }
+    graph.iter_vertices().map(block::state::State::from).for_each(|block| {
+        let (id, kind) = (block.id(), block.kind());
+        let incoming = graph.incoming_edges(id);
+        if let Some(group) = block.group() {
+            let outputs = group.iter_vertices().map(block::state::State::from).filter(|block| block.kind() == block::LayerKind::GroupOutput).count();
+            if outputs != 1 {
+                problems.push(Problem::new(Some(id), format!("{kind} #{id} needs exactly one GroupOutput block, has {outputs}")));
+            }
+        }
+        block.input_ports()
+            .iter()
+            .enumerate()
+            .filter(|(port, _)| !incoming.iter().any(|edge| edge.to_port == *port))
+            .for_each(|(_, name)| problems.push(Problem::new(Some(id), format!("{kind} #{id}: input \"{name}\" is not connected"))));
+        if !block.output_ports().is_empty() && !graph.iter_edges().any(|edge| edge.from == id) {
+            problems.push(Problem::new(Some(id), format!("{kind} #{id}: output is not used")));
+        }
+    });
+    problems
+}
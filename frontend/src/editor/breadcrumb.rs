@@ -0,0 +1,75 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+use super::board::block;
+
+pub enum Event {
+    // number of levels to go up
+    Exit(usize),
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    // groups the edited level is nested in, outermost first
+    pub path: Vec<block::Id>,
+    pub scope: Callback<Event>,
+}
+
+// shows which group is being edited, a click goes back to an outer level
+#[derive(Default)]
+pub struct Breadcrumb;
+
+impl Breadcrumb {
+    const STYLE: &'static str = "position: absolute; top: 8px; left: 50%; transform: translateX(-50%); padding: 4px 8px; \
+        background: rgba(255, 255, 255, 0.8); font: 14px sans-serif; user-select: none";
+
+    fn level_html(&self, props: &Props, title: String, levels: usize) -> Html {
+        if levels == 0 {
+            return html! { <b>{title}</b> };
+        }
+        let scope = props.scope.clone();
+        let onclick = move |_: MouseEvent| scope.emit(Event::Exit(levels));
+        html! {
+            <>
+                <a style="color: blue; cursor: pointer" {onclick}>{title}</a>
+                {" / "}
+            </>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        if props.path.is_empty() {
+            return html! {};
+        }
+        let depth = props.path.len();
+        let levels = std::iter::once("Board".to_string())
+            .chain(props.path.iter().map(|id| format!("Group #{id}")))
+            .enumerate()
+            .map(|(level, title)| self.level_html(props, title, depth - level));
+        // keep clicks on the path away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <div style={Self::STYLE} {onmousedown}>
+                {levels.collect::<Html>()}
+            </div>
+        }
+    }
+}
+
+impl yew::Component for Breadcrumb {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
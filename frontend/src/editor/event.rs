@@ -1,10 +1,45 @@
-use yew::{KeyboardEvent, MouseEvent, WheelEvent};
+use glam::DVec2;
+use yew::{DragEvent, KeyboardEvent, MouseEvent, WheelEvent};
 
 pub enum Event {
     MouseUp(MouseEvent),
     MouseDown(MouseEvent),
     MouseWheel(WheelEvent),
+    // the board element changed its size or was just shown
+    Resized,
+    // time to do the next part of a smooth zoom
+    ZoomFrame,
     KeyDown(KeyboardEvent),
-    CursorMove{new_value: glam::DVec2},
+    KeyUp(KeyboardEvent),
+    CursorMove{new_value: DVec2},
     BoardEvent(super::board::Event),
+    BlockMenu(super::block_menu::Event),
+    ArrangeMenu(super::arrange_menu::Event),
+    // right click, opens the arrange menu
+    ContextMenu(MouseEvent),
+    Inspector(super::inspector::Event),
+    Problems(super::problems::Event),
+    Breadcrumb(super::breadcrumb::Event),
+    Library(super::library::Event),
+    Minimap(super::minimap::Event),
+    // something dropped on the board, a library component if it carries a name
+    Drop(DragEvent),
+    Toolbar(super::toolbar::Event),
+    // text read from the system clipboard, if any
+    Paste(Option<String>),
+    // time to move the laid out blocks a step further
+    LayoutFrame,
+    BuildFinished(Result<common::api::CompileReport, common::api::Error>),
+    RunFinished(Result<common::api::ForwardReport, common::api::Error>),
+    ProjectSaved(Result<(), common::api::Error>),
+    Exported(Result<(), common::api::Error>),
+    ScriptSaved(Result<(), common::api::Error>),
+    ScriptRun(Result<common::api::InferenceReport, common::api::Error>),
+    ProjectLoaded(Result<common::api::LoadedProject, common::api::Error>),
+    Imported(Result<common::project::Project, common::api::Error>),
+    ComponentsListed(Result<Vec<common::library::ComponentRef>, common::api::Error>),
+    // the component goes to the given board position
+    ComponentLoaded(Result<common::library::Component, common::api::Error>, DVec2),
+    // the group block that was saved
+    ComponentSaved(super::board::block::Id, Result<common::library::ComponentRef, common::api::Error>),
 }
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use yew::{html, Callback, Html, KeyboardEvent, MouseEvent, TargetCast};
+
+use super::board::block::{self, params::{ParamSpec, ParamValue}, state::StateInterface};
+
+pub enum Event {
+    SetParam { block: block::Id, name: &'static str, value: ParamValue },
+}
+
+pub enum Message {
+    Input { spec: ParamSpec, raw: String },
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    pub block: block::StateDump,
+    pub scope: Callback<Event>,
+}
+
+// side panel with the parameters of the single selected block
+#[derive(Default)]
+pub struct Inspector {
+    // validation errors of the last input, by parameter name
+    errors: BTreeMap<&'static str, String>,
+}
+
+impl Inspector {
+    const STYLE: &'static str = "position: absolute; top: 0; right: 0; width: 260px; height: 100%; \
+        box-sizing: border-box; padding: 12px; background: rgb(245, 245, 245); \
+        border-left: 1px solid gray; font: 14px sans-serif; overflow-y: auto";
+
+    fn param_html(&self, spec: ParamSpec, value: Option<ParamValue>, ctx: &yew::Context<Self>) -> Html {
+        let onchange = ctx.link().callback(move |e: yew::Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            Message::Input { spec, raw: input.value() }
+        });
+        let error = self.errors.get(spec.name);
+        let input_style = match error {
+            Some(_) => "width: 100%; box-sizing: border-box; border: 1px solid red",
+            None => "width: 100%; box-sizing: border-box",
+        };
+        html! {
+            <div style="margin-bottom: 10px">
+                <label>
+                    <div>{spec.label}</div>
+                    <input style={input_style} value={value.map(|value| value.to_string()).unwrap_or_default()} {onchange}/>
+                </label>
+                {error.map(|error| html! {
+                    <div style="color: red; font-size: 12px">{error}</div>
+                })}
+            </div>
+        }
+    }
+}
+
+impl Inspector {
+    // an imported Op block has no layer behind it, its node attributes are only shown
+    fn attributes_html(&self, params: &block::params::Params) -> Html {
+        let hidden = [block::params::OP_TYPE, block::params::OP_INPUTS, block::params::OP_OUTPUTS];
+        let attributes = params.iter().filter(|(name, _)| !hidden.contains(name)).collect::<Vec<_>>();
+        if attributes.is_empty() {
+            return html! { <div style="color: gray">{"No attributes"}</div> };
+        }
+        attributes
+            .into_iter()
+            .map(|(name, value)| html! {
+                <div style="margin-bottom: 10px">
+                    <div>{name}</div>
+                    <div style="color: gray; overflow-wrap: anywhere">{value.to_string()}</div>
+                </div>
+            })
+            .collect::<Html>()
+    }
+}
+
+impl yew::Component for Inspector {
+    type Message = Message;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let block = &ctx.props().block;
+        let params = block.params();
+        let specs = block.kind().param_specs();
+        // keep typing and clicks inside the panel away from the board
+        let onkeydown = |e: KeyboardEvent| e.stop_propagation();
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <div style={Self::STYLE} {onkeydown} {onmousedown}>
+                <h3 style="margin-top: 0">{format!("{} #{}", block.kind().name(), block.id())}</h3>
+                {match specs.is_empty() {
+                    true if block.kind() == block::LayerKind::Op => html! {
+                        <>
+                            <div style="margin-bottom: 10px">{params.text(block::params::OP_TYPE).unwrap_or_default().to_string()}</div>
+                            {self.attributes_html(&params)}
+                        </>
+                    },
+                    true => html! { <div style="color: gray">{"No parameters"}</div> },
+                    false => specs
+                        .iter()
+                        .map(|spec| self.param_html(*spec, params.get(spec.name).cloned(), ctx))
+                        .collect::<Html>(),
+                }}
+            </div>
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Message::Input { spec, raw } => {
+                match spec.parse(&raw) {
+                    Ok(value) => {
+                        self.errors.remove(spec.name);
+                        ctx.props().scope.emit(Event::SetParam { block: ctx.props().block.id(), name: spec.name, value });
+                    }
+                    Err(error) => {
+                        self.errors.insert(spec.name, error.to_string());
+                    }
+                };
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().block.id() != old_props.block.id() {
+            self.errors.clear();
+        }
+        true
+    }
+}
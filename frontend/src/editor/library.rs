@@ -0,0 +1,76 @@
+use common::library::ComponentRef;
+use yew::{html, Callback, DragEvent, Html, MouseEvent};
+
+// data type of a dragged component name, so other drops are ignored
+pub const DRAG_FORMAT: &str = "application/x-nngraphix-component";
+
+pub enum Event {
+    // store the selected group under a name
+    SaveSelected,
+    Refresh,
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    pub components: Vec<ComponentRef>,
+    pub scope: Callback<Event>,
+}
+
+// components saved in the library, dragged onto the board to make an instance
+#[derive(Default)]
+pub struct Library;
+
+impl Library {
+    const STYLE: &'static str = "position: absolute; bottom: 48px; left: 8px; max-width: 30%; \
+        padding: 4px 8px; background: rgba(255, 255, 255, 0.8); font: 14px sans-serif; user-select: none";
+
+    fn component_html(&self, component: &ComponentRef) -> Html {
+        let name = component.name.clone();
+        let ondragstart = move |e: DragEvent| {
+            if let Some(data) = e.data_transfer() {
+                let _ = data.set_data(DRAG_FORMAT, &name);
+            }
+        };
+        html! {
+            <li draggable="true" style="cursor: grab" {ondragstart}>
+                {format!("{} v{}", component.name, component.version)}
+            </li>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        let save = props.scope.reform(|_: MouseEvent| Event::SaveSelected);
+        let refresh = props.scope.reform(|_: MouseEvent| Event::Refresh);
+        // keep clicks on the panel away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <details style={Self::STYLE} {onmousedown}>
+                <summary>{format!("Library ({})", props.components.len())}</summary>
+                <ul style="margin: 4px 0; padding-left: 20px">
+                    {props.components.iter().map(|component| self.component_html(component)).collect::<Html>()}
+                </ul>
+                <button onclick={save}>{"Save selected group"}</button>
+                <button onclick={refresh}>{"Refresh"}</button>
+            </details>
+        }
+    }
+}
+
+impl yew::Component for Library {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
@@ -0,0 +1,170 @@
+use glam::DVec2;
+use yew::{html, Callback, Html, MouseEvent, NodeRef};
+
+use super::board::block::{layer_kind, state::StateInterface};
+use super::board::state::internal;
+
+// size of the minimap on screen
+const SIZE: DVec2 = DVec2 { x: 240.0, y: 160.0 };
+// board units kept around the blocks and the view
+const PADDING: f64 = 100.0;
+
+pub enum Event {
+    // the view should be centered on this board point
+    MoveTo(DVec2),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    top_left: DVec2,
+    bottom_right: DVec2,
+    color: &'static str,
+}
+
+// what the minimap draws of the board, arrows go straight between block centers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Overview {
+    blocks: Vec<Block>,
+    arrows: Vec<(DVec2, DVec2)>,
+}
+
+impl Overview {
+    pub fn from(internal: &internal::State) -> Self {
+        let blocks = internal.graph()
+            .iter_vertices()
+            .map(super::board::block::state::State::from)
+            .map(|block| Block { top_left: block.top_left(), bottom_right: block.bottom_right(), color: layer_kind::color(block.kind()) })
+            .collect();
+        let arrows = internal.graph()
+            .iter_edges()
+            .map(|edge| (internal.block(edge.from).center(), internal.block(edge.to).center()))
+            .collect();
+        Self { blocks, arrows }
+    }
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    pub overview: Overview,
+    // top left corner and size of the part of the board on screen
+    pub view: (DVec2, DVec2),
+    pub scope: Callback<Event>,
+}
+
+#[derive(Default)]
+pub struct Minimap {
+    // area shown when the left button went down inside the minimap, kept while it is held
+    // so that the view moving under the cursor does not move the map too
+    dragging: Option<(DVec2, DVec2)>,
+    svg: NodeRef,
+}
+
+impl Minimap {
+    const STYLE: &'static str = "position: absolute; right: 8px; bottom: 8px; background: white; \
+        border: 1px solid gray; box-shadow: 2px 2px 6px rgba(0, 0, 0, 0.3); cursor: pointer";
+
+    fn area(&self, props: &Props) -> (DVec2, DVec2) {
+        self.dragging.unwrap_or_else(|| Self::fitted_area(props))
+    }
+
+    // board area covering the blocks and the view, stretched to the shape of the minimap
+    fn fitted_area(props: &Props) -> (DVec2, DVec2) {
+        let (view_top_left, view_size) = props.view;
+        let (top_left, bottom_right) = props.overview.blocks
+            .iter()
+            .fold((view_top_left, view_top_left + view_size), |(top_left, bottom_right), block| {
+                (top_left.min(block.top_left), bottom_right.max(block.bottom_right))
+            });
+        let center = (top_left + bottom_right) / 2.;
+        let size = bottom_right - top_left + 2. * PADDING;
+        let units_per_pixel = (size / SIZE).max_element();
+        (center - SIZE * units_per_pixel / 2., SIZE * units_per_pixel)
+    }
+
+    // offsets are taken from the minimap itself, not from the block or line under the cursor
+    fn board_pos(&self, props: &Props, e: &MouseEvent) -> Option<DVec2> {
+        let svg = self.svg.cast::<web_sys::Element>()?.get_bounding_client_rect();
+        let offset = DVec2::new(e.client_x() as f64 - svg.left(), e.client_y() as f64 - svg.top());
+        let (top_left, size) = self.area(props);
+        Some(top_left + offset / SIZE * size)
+    }
+
+    fn block_html(block: &Block) -> Html {
+        let size = block.bottom_right - block.top_left;
+        html! {
+            <rect x={block.top_left.x.to_string()} y={block.top_left.y.to_string()}
+                  width={size.x.to_string()} height={size.y.to_string()} fill={block.color}/>
+        }
+    }
+
+    fn arrow_html((from, to): &(DVec2, DVec2)) -> Html {
+        html! {
+            <line x1={from.x.to_string()} y1={from.y.to_string()} x2={to.x.to_string()} y2={to.y.to_string()}
+                  stroke="gray" stroke-width="1" vector-effect="non-scaling-stroke"/>
+        }
+    }
+
+    fn view_html(props: &Props) -> Html {
+        let (top_left, size) = props.view;
+        html! {
+            <rect x={top_left.x.to_string()} y={top_left.y.to_string()}
+                  width={size.x.to_string()} height={size.y.to_string()}
+                  fill="rgb(0,0,255)" fill-opacity="0.1" stroke="blue" stroke-width="1" vector-effect="non-scaling-stroke"/>
+        }
+    }
+}
+
+pub enum Message {
+    Press(MouseEvent),
+    Move(MouseEvent),
+    Release,
+}
+
+impl yew::Component for Minimap {
+    type Message = Message;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let props = ctx.props();
+        let (top_left, size) = self.area(props);
+        let onmousedown = ctx.link().callback(|e: MouseEvent| {
+            // keep the board from starting a selection
+            e.stop_propagation();
+            Message::Press(e)
+        });
+        let onmousemove = ctx.link().callback(Message::Move);
+        let onmouseup = ctx.link().callback(|_: MouseEvent| Message::Release);
+        let onmouseleave = ctx.link().callback(|_: MouseEvent| Message::Release);
+        html! {
+            <svg ref={self.svg.clone()} style={Self::STYLE} width={SIZE.x.to_string()} height={SIZE.y.to_string()}
+                 viewBox={format!("{} {} {} {}", top_left.x, top_left.y, size.x, size.y)}
+                 {onmousedown} {onmousemove} {onmouseup} {onmouseleave}>
+                {for props.overview.arrows.iter().map(Self::arrow_html)}
+                {for props.overview.blocks.iter().map(Self::block_html)}
+                {Self::view_html(props)}
+            </svg>
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let e = match msg {
+            Message::Press(e) if e.button() == 0 => {
+                self.dragging = Some(Self::fitted_area(ctx.props()));
+                e
+            }
+            Message::Move(e) if self.dragging.is_some() => e,
+            // the map fits the view again
+            Message::Release => return self.dragging.take().is_some(),
+            _ => return false,
+        };
+        if let Some(pos) = self.board_pos(ctx.props(), &e) {
+            ctx.props().scope.emit(Event::MoveTo(pos));
+        }
+        false
+    }
+}
@@ -0,0 +1,68 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+use super::board::{block, validation::Problem};
+
+pub enum Event {
+    Select(block::Id),
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    pub problems: Vec<Problem>,
+    pub scope: Callback<Event>,
+}
+
+// list of validation problems under the toolbar, a click selects the block
+#[derive(Default)]
+pub struct Problems;
+
+impl Problems {
+    const STYLE: &'static str = "position: absolute; top: 40px; left: 8px; max-width: 40%; \
+        padding: 4px 8px; background: rgba(255, 255, 255, 0.8); font: 14px sans-serif";
+
+    fn problem_html(&self, props: &Props, problem: &Problem) -> Html {
+        let onclick = problem.block.map(|block| props.scope.reform(move |_: MouseEvent| Event::Select(block)));
+        let style = match problem.block {
+            Some(_) => "color: darkred; cursor: pointer",
+            None => "color: darkred",
+        };
+        html! {
+            <li {style} {onclick}>{&problem.message}</li>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        if props.problems.is_empty() {
+            return html! {};
+        }
+        // keep clicks on the panel away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <details style={Self::STYLE} {onmousedown}>
+                <summary>{format!("{} problems", props.problems.len())}</summary>
+                <ul style="margin: 4px 0; padding-left: 20px">
+                    {props.problems.iter().map(|problem| self.problem_html(props, problem)).collect::<Html>()}
+                </ul>
+            </details>
+        }
+    }
+}
+
+impl yew::Component for Problems {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
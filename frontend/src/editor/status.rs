@@ -0,0 +1,82 @@
+use common::api;
+use yew::{html, Html};
+
+// outcome of the last backend command
+#[derive(PartialEq, Debug, Clone, Default)]
+pub enum Status {
+    #[default]
+    Idle,
+    Running(&'static str),
+    Built(api::CompileReport),
+    Inferred(api::InferenceReport),
+    // a project was loaded with some library components updated
+    Updated(common::library::UpdateReport),
+    // a command without a report finished
+    Done(&'static str),
+    Failed(api::Error),
+}
+
+impl Status {
+    const STYLE: &'static str = "position: absolute; bottom: 8px; left: 8px; max-width: 50%; \
+        padding: 4px 8px; background: rgba(255, 255, 255, 0.8); font: 14px sans-serif";
+
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running(_))
+    }
+
+    fn built_html(report: &api::CompileReport) -> Html {
+        html! {
+            <details>
+                <summary>{format!("Built: {} parameters", report.total_params)}</summary>
+                {report.blocks.iter().map(|block| html! {
+                    <div>{format!("#{} {}: {}", block.id, block.kind, block.params)}</div>
+                }).collect::<Html>()}
+            </details>
+        }
+    }
+
+    fn inferred_html(report: &api::InferenceReport) -> Html {
+        let values = report.values.iter().map(|value| format!("{value:.4}")).collect::<Vec<_>>().join(", ");
+        html! {
+            <details>
+                <summary>{format!("Output {:?}", report.shape)}</summary>
+                <div style="overflow-wrap: anywhere">{values}</div>
+            </details>
+        }
+    }
+
+    fn updated_html(report: &common::library::UpdateReport) -> Html {
+        html! {
+            <details>
+                <summary>{format!("Project loaded, updated components: {}", report.updated.join(", "))}</summary>
+                {report.dropped.iter().map(|dropped| {
+                    let edge = dropped.edge;
+                    let place = match dropped.path.as_slice() {
+                        [] => String::new(),
+                        path => format!(" in group #{}", path.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" > #")),
+                    };
+                    html! {
+                        <div>{format!("Removed the arrow #{} -> #{} port {}{place}, the port is gone", edge.from, edge.to, edge.to_port + 1)}</div>
+                    }
+                }).collect::<Html>()}
+            </details>
+        }
+    }
+
+    pub fn html(&self) -> Html {
+        let content = match self {
+            Self::Idle => return html! {},
+            Self::Running(what) => html! { {format!("{what}...")} },
+            Self::Built(report) => Self::built_html(report),
+            Self::Inferred(report) => Self::inferred_html(report),
+            Self::Updated(report) => Self::updated_html(report),
+            Self::Done(what) => html! { {*what} },
+            Self::Failed(error) => html! {
+                <span style="color: red">{format!("Failed: {error}")}</span>
+            },
+        };
+        html! {
+            <div style={Self::STYLE}>{content}</div>
+        }
+    }
+}
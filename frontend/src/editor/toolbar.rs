@@ -0,0 +1,107 @@
+use yew::{html, Callback, Html, KeyboardEvent, MouseEvent, TargetCast};
+
+pub enum Event {
+    Build,
+    Run,
+    Export,
+    Import,
+    SaveScript,
+    RunScript,
+    Layout,
+    SetSnap(bool),
+    SetMinimap(bool),
+    SetSampleInput(String),
+}
+
+#[derive(PartialEq, yew::Properties)]
+pub struct Props {
+    // a backend command is running
+    pub busy: bool,
+    // comma separated values fed to the Input block, random when empty
+    pub sample_input: String,
+    // blocks are placed on the grid
+    pub snap: bool,
+    pub minimap: bool,
+    pub scope: Callback<Event>,
+}
+
+#[derive(Default)]
+pub struct Toolbar;
+
+impl Toolbar {
+    const STYLE: &'static str = "position: absolute; top: 8px; left: 8px; display: flex; gap: 4px; \
+        font: 14px sans-serif";
+
+    fn button_html(&self, props: &Props, title: &'static str, make_event: fn() -> Event) -> Html {
+        let scope = props.scope.clone();
+        let onclick = move |_: MouseEvent| scope.emit(make_event());
+        html! {
+            <button disabled={props.busy} {onclick}>{title}</button>
+        }
+    }
+
+    fn sample_input_html(&self, props: &Props) -> Html {
+        let scope = props.scope.clone();
+        let onchange = move |e: yew::Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            scope.emit(Event::SetSampleInput(input.value()));
+        };
+        // typing must not trigger board shortcuts
+        let onkeydown = |e: KeyboardEvent| e.stop_propagation();
+        html! {
+            <input placeholder="sample input (random)" value={props.sample_input.clone()} {onchange} {onkeydown}/>
+        }
+    }
+
+    fn checkbox_html(&self, props: &Props, title: &'static str, checked: bool, make_event: fn(bool) -> Event) -> Html {
+        let scope = props.scope.clone();
+        let onchange = move |e: yew::Event| {
+            let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+            scope.emit(make_event(input.checked()));
+        };
+        html! {
+            <label>
+                <input type="checkbox" {checked} {onchange}/>
+                {title}
+            </label>
+        }
+    }
+
+    pub fn html(&self, props: &Props) -> Html {
+        // keep clicks on the toolbar away from the board
+        let onmousedown = |e: MouseEvent| e.stop_propagation();
+        html! {
+            <div style={Self::STYLE} {onmousedown}>
+                {self.button_html(props, "Build", || Event::Build)}
+                {self.button_html(props, "Run", || Event::Run)}
+                {self.button_html(props, "Export", || Event::Export)}
+                {self.button_html(props, "Import", || Event::Import)}
+                {self.button_html(props, "Save .pt", || Event::SaveScript)}
+                {self.button_html(props, "Run .pt", || Event::RunScript)}
+                {self.button_html(props, "Layout", || Event::Layout)}
+                {self.checkbox_html(props, "Snap to grid", props.snap, Event::SetSnap)}
+                {self.checkbox_html(props, "Minimap", props.minimap, Event::SetMinimap)}
+                {self.sample_input_html(props)}
+            </div>
+        }
+    }
+}
+
+impl yew::Component for Toolbar {
+    type Message = Event;
+
+    type Properties = Props;
+
+    fn create(_: &yew::Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        self.html(ctx.props())
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        ctx.props().scope.emit(msg);
+        false
+    }
+}
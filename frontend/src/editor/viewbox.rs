@@ -1,10 +1,30 @@
 pub mod basic;
 mod common;
 pub mod dragged;
+pub mod ready;
+pub mod zoom;
+
+pub use common::{Screen, MAX_SCALE, MIN_SCALE};
+
+// screen pixels the view moves by on a key press
+pub const PAN_STEP: f64 = 50.0;
+const LINE_HEIGHT: f64 = 16.0;
+const PAGE_HEIGHT: f64 = 800.0;
+
+// how far the wheel or the fingers on a trackpad went, in pixels whatever unit the event uses
+pub fn wheel_pixels(event: &web_sys::WheelEvent) -> glam::DVec2 {
+    let delta = glam::DVec2::new(event.delta_x(), event.delta_y());
+    match event.delta_mode() {
+        web_sys::WheelEvent::DOM_DELTA_LINE => delta * LINE_HEIGHT,
+        web_sys::WheelEvent::DOM_DELTA_PAGE => delta * PAGE_HEIGHT,
+        _ => delta,
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum State {
     Basic(basic::State),
+    Ready(ready::State),
     Dragged(dragged::State),
 }
 
@@ -19,17 +39,100 @@ impl State {
         self
     }
 
+    // restored viewboxes are never dragged
+    pub fn from_project(viewbox: &::common::project::Viewbox, limits: (f64, f64)) -> Self {
+        Self::Basic(basic::State::from(common::Viewbox::from_project(viewbox, limits)))
+    }
+
+    pub fn to_project(&self) -> ::common::project::Viewbox {
+        match self {
+            Self::Basic(s) => s.viewbox().to_project(),
+            Self::Ready(s) => s.viewbox().to_project(),
+            Self::Dragged(s) => s.viewbox().to_project(),
+        }
+    }
+
+    // screen pixels per board unit
+    pub fn get_scale(&self) -> f64 {
+        self.to_project().scale
+    }
+
+    pub fn visible_area(&self) -> (glam::DVec2, glam::DVec2) {
+        match self {
+            Self::Basic(s) => s.viewbox().visible_area(),
+            Self::Ready(s) => s.viewbox().visible_area(),
+            Self::Dragged(s) => s.viewbox().visible_area(),
+        }
+    }
+
     pub fn make_viewbox_str(&self) -> String {
         match self {
             Self::Basic(s) => s.make_viewbox_str(),
+            Self::Ready(s) => s.make_viewbox_str(),
             Self::Dragged(s) => s.make_viewbox_str(),
         }
     }
 
-    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64) -> &mut Self {
+    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64, limits: (f64, f64)) -> &mut Self {
+        match self {
+            Self::Basic(s) => {s.scale(cursor, factor, limits);}
+            Self::Ready(s) => {s.scale(cursor, factor, limits);}
+            Self::Dragged(s) => {s.scale(cursor, factor, limits);}
+        };
+        self
+    }
+
+    // None until the element is measured
+    pub fn get_screen(&self) -> Option<Screen> {
+        match self {
+            Self::Basic(s) => s.viewbox().get_screen(),
+            Self::Ready(s) => s.viewbox().get_screen(),
+            Self::Dragged(s) => s.viewbox().get_screen(),
+        }
+    }
+
+    pub fn set_screen(&mut self, screen: Screen) -> &mut Self {
+        match self {
+            Self::Basic(s) => {s.set_screen(screen);}
+            Self::Ready(s) => {s.set_screen(screen);}
+            Self::Dragged(s) => {s.set_screen(screen);}
+        };
+        self
+    }
+
+    // the delta is in screen pixels
+    pub fn pan(&mut self, delta: glam::DVec2) -> &mut Self {
+        match self {
+            Self::Basic(s) => {s.pan(delta);}
+            Self::Ready(s) => {s.pan(delta);}
+            Self::Dragged(s) => {s.pan(delta);}
+        };
+        self
+    }
+
+    // the hand shows that the left button moves the view
+    pub fn cursor_style(&self) -> &'static str {
+        match self {
+            Self::Basic(_) => "",
+            Self::Ready(_) => "cursor: grab",
+            Self::Dragged(_) => "cursor: grabbing",
+        }
+    }
+
+    pub fn center_on(&mut self, center: glam::DVec2) -> &mut Self {
+        match self {
+            Self::Basic(s) => {s.center_on(center);}
+            Self::Ready(s) => {s.center_on(center);}
+            Self::Dragged(s) => {s.center_on(center);}
+        };
+        self
+    }
+
+    pub fn fit(&mut self, top_left: glam::DVec2, bottom_right: glam::DVec2, limits: (f64, f64)) -> &mut Self {
         match self {
-            Self::Basic(s) => {s.scale(cursor, factor);}
-            Self::Dragged(s) => {s.scale(cursor, factor);}
+            Self::Basic(s) => {s.fit(top_left, bottom_right, limits);}
+            Self::Ready(s) => {s.fit(top_left, bottom_right, limits);}
+            Self::Dragged(s) => {s.fit(top_left, bottom_right, limits);}
         };
         self
     }
@@ -38,12 +141,14 @@ impl State {
     pub fn to_app_coords(&self, board_coords: glam::DVec2) -> glam::DVec2 {
         match self {
             Self::Basic(s) => s.to_app_coords(board_coords),
+            Self::Ready(s) => s.to_app_coords(board_coords),
             Self::Dragged(s) => s.to_app_coords(board_coords),
         }
     }
     pub fn to_board_coords(&self, app_coords: glam::DVec2) -> glam::DVec2 {
         match self {
             Self::Basic(s) => s.to_board_coords(app_coords),
+            Self::Ready(s) => s.to_board_coords(app_coords),
             Self::Dragged(s) => s.to_board_coords(app_coords),
         }
     }
@@ -53,6 +158,7 @@ impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             Self::Basic(_) => "Basic",
+            Self::Ready(_) => "Ready",
             Self::Dragged(_) => "Dragged",
         })
     }
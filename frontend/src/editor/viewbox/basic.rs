@@ -13,14 +13,42 @@ impl State {
     }
 
     pub fn drag(self) -> super::dragged::State {
-        super::dragged::State::from(self.0)
+        super::dragged::State::from(self.0, false)
     }
 
-    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64) -> &mut Self {
-        self.0.scale(cursor, factor);
+    pub fn hold_space(self) -> super::ready::State {
+        super::ready::State::from(self.0)
+    }
+
+    pub fn pan(&mut self, delta: glam::DVec2) -> &mut Self {
+        self.0.pan(delta);
+        self
+    }
+
+    pub fn set_screen(&mut self, screen: super::Screen) -> &mut Self {
+        self.0.set_screen(screen);
+        self
+    }
+
+    pub fn center_on(&mut self, center: glam::DVec2) -> &mut Self {
+        self.0.center_on(center);
         self
     }
 
+    pub fn fit(&mut self, top_left: glam::DVec2, bottom_right: glam::DVec2, limits: (f64, f64)) -> &mut Self {
+        self.0.fit(top_left, bottom_right, limits);
+        self
+    }
+
+    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64, limits: (f64, f64)) -> &mut Self {
+        self.0.scale(cursor, factor, limits);
+        self
+    }
+
+    pub(super) fn viewbox(&self) -> &Viewbox {
+        &self.0
+    }
+
     pub fn make_viewbox_str(&self) -> String {
         self.0.make_viewbox_str()
     }
@@ -1,21 +1,39 @@
 use glam::DVec2;
 
-const SCALES: &[f64] = &[0.25, 0.33, 0.5, 0.66, 0.75, 0.9, 1.0, 1.1, 1.2, 1.5, 2.0, 3.0, 4.0];
-const DEFAULT_SCALE_INDEX: usize = 6;
+// default limits of the zoom, the editor can be given others
+pub const MIN_SCALE: f64 = 0.1;
+pub const MAX_SCALE: f64 = 8.0;
+// screen pixels left around a box zoomed to
+const FIT_MARGIN: f64 = 40.0;
+
+// where the SVG element is in the window, in client pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Screen {
+    pub pos: DVec2,
+    pub size: DVec2,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Viewbox {
     pos: DVec2, // points to top left corner
-    scale_index: usize, // index in SCALES array
+    scale: f64, // screen pixels per board unit
+    screen: Option<Screen>, // the whole window until the element is measured
 }
 
 impl Default for Viewbox {
     fn default() -> Self {
-        Self { pos: DVec2::default(), scale_index: DEFAULT_SCALE_INDEX }
+        Self { pos: DVec2::default(), scale: 1.0, screen: None }
     }
 }
 
 impl Viewbox {
+    // the scale is kept within the zoom limits
+    pub fn from_project(viewbox: &::common::project::Viewbox, limits: (f64, f64)) -> Self {
+        Self { pos: DVec2::new(viewbox.x, viewbox.y), scale: viewbox.scale.clamp(limits.0, limits.1), screen: None }
+    }
+    pub fn to_project(&self) -> ::common::project::Viewbox {
+        ::common::project::Viewbox { x: self.pos.x, y: self.pos.y, scale: self.scale }
+    }
     fn get_window_size() -> DVec2 {
         let window = web_sys::window().expect("There should be a window");
         DVec2 {
@@ -31,34 +49,61 @@ impl Viewbox {
                 .expect("The height should be a number"),
         }
     }
+    fn screen(&self) -> Screen {
+        self.screen.unwrap_or_else(|| Screen { pos: DVec2::ZERO, size: Self::get_window_size() })
+    }
+    pub fn get_screen(&self) -> Option<Screen> {
+        self.screen
+    }
+    pub fn set_screen(&mut self, screen: Screen) {
+        self.screen = Some(screen);
+    }
     fn get_scale(&self) -> DVec2 {
-        DVec2 { x: SCALES[self.scale_index], y: SCALES[self.scale_index] }
+        DVec2::splat(self.scale)
     }
     fn board_size(&self) -> DVec2 {
-        self.to_board_coords(Self::get_window_size()) - self.to_board_coords(DVec2::default())
+        self.screen().size / self.get_scale()
     }
+    // app coordinates are the client ones of mouse events, the board starts where the element does
     #[allow(unused)]
     pub fn to_app_coords(&self, board_coords: DVec2) -> DVec2 {
-        (board_coords - self.pos) * self.get_scale()
+        (board_coords - self.pos) * self.get_scale() + self.screen().pos
     }
     pub fn to_board_coords(&self, app_coords: DVec2) -> DVec2 {
-        app_coords / self.get_scale() + self.pos
+        (app_coords - self.screen().pos) / self.get_scale() + self.pos
     }
 
-    pub fn scale(&mut self, cursor: DVec2, factor: f64) {
+    // the board point under the cursor stays there
+    pub fn scale(&mut self, cursor: DVec2, factor: f64, limits: (f64, f64)) {
         let cursor_board_pos = self.to_board_coords(cursor);
-        if factor > 0. && self.scale_index > 0 {
-            self.scale_index -= 1;
-        } else if factor < 0. && self.scale_index < SCALES.len() - 1 {
-            self.scale_index += 1;
-        }
+        self.scale = (self.scale * factor).clamp(limits.0, limits.1);
         let new_cursor_board_pos = self.to_board_coords(cursor);
         self.pos += cursor_board_pos - new_cursor_board_pos;
     }
+    // the box fills the element with a margin around it and is centered in it
+    pub fn fit(&mut self, top_left: DVec2, bottom_right: DVec2, limits: (f64, f64)) {
+        let screen = self.screen().size;
+        let room = (screen - 2. * FIT_MARGIN).max(DVec2::ONE);
+        let size = (bottom_right - top_left).max(DVec2::ONE);
+        self.scale = (room / size).min_element().clamp(limits.0, limits.1);
+        self.pos = (top_left + bottom_right) / 2. - screen / (2. * self.scale);
+    }
+    // the delta is in screen pixels
+    pub fn pan(&mut self, delta: DVec2) {
+        self.pos += delta / self.scale;
+    }
+    pub fn center_on(&mut self, center: DVec2) {
+        self.pos = center - self.board_size() / 2.;
+    }
     pub fn move_box(&mut self, delta: DVec2) {
         self.pos += delta;
     }
 
+    // top left corner and size of the part of the board on screen
+    pub fn visible_area(&self) -> (DVec2, DVec2) {
+        (self.pos, self.board_size())
+    }
+
     pub fn make_viewbox_str(&self) -> String {
         let board_size = self.board_size();
         format!(
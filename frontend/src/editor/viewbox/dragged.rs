@@ -1,40 +1,77 @@
 use super::common::Viewbox;
 
 #[derive(Debug, Clone, Default, PartialEq)]
-pub struct State (Viewbox);
+pub struct State {
+    viewbox: Viewbox,
+    // the drag started with space held and space is still down
+    space_held: bool,
+}
 
 impl State {
-    pub(super) fn from(viewbox: Viewbox) -> Self {
-        Self(viewbox)
+    pub(super) fn from(viewbox: Viewbox, space_held: bool) -> Self {
+        Self { viewbox, space_held }
     }
 
     pub fn to_states_enum(self) -> super::State {
         super::State::Dragged(self)
     }
 
-    pub fn drop(self) -> super::basic::State {
-        super::basic::State::from(self.0)
+    // back to waiting for the next drag while space is still held
+    pub fn drop(self) -> super::State {
+        match self.space_held {
+            true => super::ready::State::from(self.viewbox).to_states_enum(),
+            false => super::basic::State::from(self.viewbox).to_states_enum(),
+        }
     }
 
-    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64) -> &mut Self {
-        self.0.scale(cursor, factor);
+    pub fn release_space(&mut self) -> &mut Self {
+        self.space_held = false;
+        self
+    }
+
+    pub fn pan(&mut self, delta: glam::DVec2) -> &mut Self {
+        self.viewbox.pan(delta);
+        self
+    }
+
+    pub fn set_screen(&mut self, screen: super::Screen) -> &mut Self {
+        self.viewbox.set_screen(screen);
+        self
+    }
+
+    pub fn center_on(&mut self, center: glam::DVec2) -> &mut Self {
+        self.viewbox.center_on(center);
+        self
+    }
+
+    pub fn fit(&mut self, top_left: glam::DVec2, bottom_right: glam::DVec2, limits: (f64, f64)) -> &mut Self {
+        self.viewbox.fit(top_left, bottom_right, limits);
+        self
+    }
+
+    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64, limits: (f64, f64)) -> &mut Self {
+        self.viewbox.scale(cursor, factor, limits);
         self
     }
 
     pub fn move_box(&mut self, delta: glam::DVec2) -> &mut Self {
-        self.0.move_box(delta);
+        self.viewbox.move_box(delta);
         self
     }
 
+    pub(super) fn viewbox(&self) -> &Viewbox {
+        &self.viewbox
+    }
+
     pub fn make_viewbox_str(&self) -> String {
-        self.0.make_viewbox_str()
+        self.viewbox.make_viewbox_str()
     }
 
     #[allow(unused)]
     pub fn to_app_coords(&self, board_coords: glam::DVec2) -> glam::DVec2 {
-        self.0.to_app_coords(board_coords)
+        self.viewbox.to_app_coords(board_coords)
     }
     pub fn to_board_coords(&self, app_coords: glam::DVec2) -> glam::DVec2 {
-        self.0.to_board_coords(app_coords)
+        self.viewbox.to_board_coords(app_coords)
     }
 }
\ No newline at end of file
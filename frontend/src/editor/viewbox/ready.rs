@@ -0,0 +1,64 @@
+use super::common::Viewbox;
+
+// space is held, dragging with the left button moves the view
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct State (Viewbox);
+
+impl State {
+    pub(super) fn from(viewbox: Viewbox) -> Self {
+        State(viewbox)
+    }
+
+    pub fn to_states_enum(self) -> super::State {
+        super::State::Ready(self)
+    }
+
+    pub fn drag(self) -> super::dragged::State {
+        super::dragged::State::from(self.0, true)
+    }
+
+    pub fn release_space(self) -> super::basic::State {
+        super::basic::State::from(self.0)
+    }
+
+    pub fn pan(&mut self, delta: glam::DVec2) -> &mut Self {
+        self.0.pan(delta);
+        self
+    }
+
+    pub fn set_screen(&mut self, screen: super::Screen) -> &mut Self {
+        self.0.set_screen(screen);
+        self
+    }
+
+    pub fn center_on(&mut self, center: glam::DVec2) -> &mut Self {
+        self.0.center_on(center);
+        self
+    }
+
+    pub fn fit(&mut self, top_left: glam::DVec2, bottom_right: glam::DVec2, limits: (f64, f64)) -> &mut Self {
+        self.0.fit(top_left, bottom_right, limits);
+        self
+    }
+
+    pub fn scale(&mut self, cursor: glam::DVec2, factor: f64, limits: (f64, f64)) -> &mut Self {
+        self.0.scale(cursor, factor, limits);
+        self
+    }
+
+    pub(super) fn viewbox(&self) -> &Viewbox {
+        &self.0
+    }
+
+    pub fn make_viewbox_str(&self) -> String {
+        self.0.make_viewbox_str()
+    }
+
+    #[allow(unused)]
+    pub fn to_app_coords(&self, board_coords: glam::DVec2) -> glam::DVec2 {
+        self.0.to_app_coords(board_coords)
+    }
+    pub fn to_board_coords(&self, app_coords: glam::DVec2) -> glam::DVec2 {
+        self.0.to_board_coords(app_coords)
+    }
+}
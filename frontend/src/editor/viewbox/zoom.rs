@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use glam::DVec2;
+
+pub const FRAME_TIME: Duration = Duration::from_millis(16);
+// part of the zoom left that every frame does
+const EASING: f64 = 0.3;
+// below this the zoom left is not noticeable
+const DONE: f64 = 1e-3;
+
+// zoom per pixel scrolled
+const SPEED: f64 = 0.01;
+// a wheel notch sends a hundred pixels at once, a pinch a few at a time
+const MAX_STEP: f64 = 20.0;
+
+// factor to scale by, trackpads pinch as a wheel with ctrl held
+pub fn wheel_factor(event: &web_sys::WheelEvent) -> f64 {
+    let pixels = super::wheel_pixels(event).y.clamp(-MAX_STEP, MAX_STEP);
+    (-pixels * SPEED).exp()
+}
+
+// a zoom spread over frames, the wheel can add to it while it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zoom {
+    cursor: DVec2,
+    // logarithm of the factor still to apply
+    left: f64,
+}
+
+impl Zoom {
+    pub fn new(cursor: DVec2, factor: f64, scale: f64, limits: (f64, f64)) -> Self {
+        let mut zoom = Self { cursor, left: 0. };
+        zoom.add(cursor, factor, scale, limits);
+        zoom
+    }
+
+    // the zoom left never goes past the limits from the current scale, or scrolling back would be swallowed
+    pub fn add(&mut self, cursor: DVec2, factor: f64, scale: f64, limits: (f64, f64)) {
+        self.cursor = cursor;
+        self.left = (self.left + factor.ln()).clamp((limits.0 / scale).ln(), (limits.1 / scale).ln());
+    }
+
+    // cursor and factor of the next frame, None once done
+    pub fn next(&mut self) -> Option<(DVec2, f64)> {
+        if self.left.abs() < DONE {
+            return None;
+        }
+        let step = match self.left.abs() < DONE / EASING {
+            true => self.left,
+            false => self.left * EASING,
+        };
+        self.left -= step;
+        Some((self.cursor, step.exp()))
+    }
+}
@@ -1,5 +1,7 @@
 use yew::prelude::*;
 
+mod backend;
+mod clipboard;
 mod editor;
 mod tools;
 
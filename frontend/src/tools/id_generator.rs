@@ -6,6 +6,17 @@ pub struct IdGen {
     last: Id,
 }
 
+impl IdGen {
+    // continues after ids that are already taken
+    pub fn after(last: Id) -> Self {
+        Self { last }
+    }
+
+    pub fn last_id(&self) -> Id {
+        self.last
+    }
+}
+
 impl Iterator for IdGen {
     type Item = Id;
 
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use common::{api, graph::Graph, library::{Component, ComponentRef}, project::{Group, Project}};
+use tauri::AppHandle;
+
+use crate::{export, import, library::Library, model::GraphModule, project, torchscript};
+
+#[tauri::command]
+pub fn compile(graph: Graph) -> Result<api::CompileReport, api::Error> {
+    GraphModule::build(&graph).map(|model| model.report())
+}
+
+#[tauri::command]
+pub fn run_forward(graph: Graph, input: api::SampleInput) -> Result<api::ForwardReport, api::Error> {
+    GraphModule::build(&graph)?.run(&input)
+}
+
+#[tauri::command]
+pub fn export_model(graph: Graph, path: String) -> Result<(), api::Error> {
+    export::export(&graph, Path::new(&path))
+}
+
+// the model is opened as a new project
+#[tauri::command]
+pub fn import_model(path: String) -> Result<Project, api::Error> {
+    import::import(Path::new(&path))
+}
+
+// the weights file, if any, is one saved next to an earlier model of the board, as `model.ot` for `model.pt`
+#[tauri::command]
+pub fn save_torchscript(graph: Graph, path: String, weights: Option<String>) -> Result<(), api::Error> {
+    torchscript::save(&graph, Path::new(&path), weights.as_deref().map(Path::new))
+}
+
+// the sample takes the shape saved with the model, or the one of the Input block of the board
+#[tauri::command]
+pub fn run_torchscript(path: String, graph: Graph, input: api::SampleInput) -> Result<api::InferenceReport, api::Error> {
+    torchscript::run(Path::new(&path), &graph, &input)
+}
+
+#[tauri::command]
+pub fn save_project(path: String, project: Project) -> Result<(), api::Error> {
+    project::save(Path::new(&path), &project)
+}
+
+// instances of library components are brought up to their latest versions
+#[tauri::command]
+pub fn load_project(app: AppHandle, path: String) -> Result<api::LoadedProject, api::Error> {
+    let mut project = project::load(Path::new(&path))?;
+    let update = project.update_components(&Library::open(&app)?.components()?);
+    project.validate()?;
+    Ok(api::LoadedProject { project, update })
+}
+
+#[tauri::command]
+pub fn list_components(app: AppHandle) -> Result<Vec<ComponentRef>, api::Error> {
+    Library::open(&app)?.list()
+}
+
+#[tauri::command]
+pub fn load_component(app: AppHandle, name: String) -> Result<Component, api::Error> {
+    Library::open(&app)?.load(&name)
+}
+
+#[tauri::command]
+pub fn save_component(app: AppHandle, name: String, group: Group) -> Result<ComponentRef, api::Error> {
+    Library::open(&app)?.save(&name, group)
+}
@@ -0,0 +1,85 @@
+mod onnx;
+mod python;
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use common::{api, graph::{Graph, Vertex}, group::Flat, layer::LayerKind, shape::Shape, Id};
+
+use crate::model;
+
+// the board graph checked and flattened for the exporters
+pub struct Prepared {
+    flat: Flat,
+    // blocks in the order they are evaluated
+    order: Vec<Id>,
+    // of a single sample, without the batch dimension
+    shapes: BTreeMap<Id, Shape>,
+    input: Id,
+    output: Id,
+}
+
+impl Prepared {
+    pub fn new(graph: &Graph) -> Result<Self, api::Error> {
+        graph.check_groups()?;
+        let flat = graph.flatten();
+        let error = |error| flat.top_level_error(error);
+        let input = model::single_block(&flat.graph, LayerKind::Input).map_err(error)?;
+        let output = model::single_block(&flat.graph, LayerKind::Output).map_err(error)?;
+        let shapes = flat.graph.infer_shapes().map_err(error)?;
+        let order = flat.graph.topological_order().map_err(error)?.into_iter().map(|vertex| vertex.id).collect();
+        Ok(Self { flat, order, shapes, input, output })
+    }
+
+    fn vertex(&self, id: Id) -> &Vertex {
+        self.flat.graph.vertex(id).unwrap()
+    }
+
+    fn vertices(&self) -> impl Iterator<Item = &Vertex> {
+        self.order.iter().map(|id| self.vertex(*id))
+    }
+
+    fn inputs(&self, id: Id) -> Vec<Id> {
+        self.flat.graph.inputs(id)
+    }
+
+    // stable across exports: the kind and the ids of the enclosing groups and of the block, e.g. `conv2d_4_2`
+    fn name(&self, id: Id) -> String {
+        let path = self.flat.origin.get(&id).cloned().unwrap_or_else(|| vec![id]);
+        let path = path.iter().map(Id::to_string).collect::<Vec<_>>().join("_");
+        format!("{}_{path}", variable_prefix(self.vertex(id).kind))
+    }
+}
+
+fn variable_prefix(kind: LayerKind) -> &'static str {
+    match kind {
+        LayerKind::Input => "input",
+        LayerKind::Linear => "linear",
+        LayerKind::Conv2d => "conv2d",
+        LayerKind::MaxPool2d => "max_pool2d",
+        LayerKind::BatchNorm => "batch_norm",
+        LayerKind::ReLU => "relu",
+        LayerKind::LeakyReLU => "leaky_relu",
+        LayerKind::Dropout => "dropout",
+        LayerKind::Flatten => "flatten",
+        LayerKind::Softmax => "softmax",
+        LayerKind::Add => "add",
+        LayerKind::Concat => "concat",
+        LayerKind::Identity | LayerKind::GroupInput | LayerKind::GroupOutput => "identity",
+        LayerKind::Op => "op",
+        LayerKind::Group => "group",
+        LayerKind::Output => "output",
+    }
+}
+
+// the format is picked by the file extension
+pub fn export(graph: &Graph, path: &Path) -> Result<(), api::Error> {
+    let prepared = Prepared::new(graph)?;
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    let contents = match extension {
+        "py" => python::generate(&prepared).into_bytes(),
+        // freshly initialised weights, as in a model built from the board for a run
+        "onnx" => onnx::generate(&prepared, &model::GraphModule::build(graph)?)?,
+        _ => return Err(api::Error::new(format!("cannot export to \"{}\", expected a .py or .onnx file", path.display()))),
+    };
+    fs::write(path, contents).map_err(|error| api::Error::new(format!("cannot write {}: {error}", path.display())))
+}
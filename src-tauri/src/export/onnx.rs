@@ -0,0 +1,205 @@
+use common::{api, graph::Vertex, layer::LayerKind, shape::Shape};
+use prost::Message;
+use tch::{TchError, Tensor};
+
+use super::Prepared;
+use crate::{model::{GraphModule, BATCH_NORM_EPS, BATCH_NORM_MOMENTUM}, proto::*};
+
+// opset 13 came with ir version 7, both are read by every current runtime
+const IR_VERSION: i64 = 7;
+const OPSET_VERSION: i64 = 13;
+const GRAPH_INPUT: &str = "input";
+// the batch size is left to the runtime
+const BATCH_DIM: &str = "batch";
+
+fn int(name: &str, value: i64) -> AttributeProto {
+    AttributeProto { name: name.to_string(), i: Some(value), r#type: ATTRIBUTE_INT, ..Default::default() }
+}
+
+fn ints(name: &str, values: Vec<i64>) -> AttributeProto {
+    AttributeProto { name: name.to_string(), ints: values, r#type: ATTRIBUTE_INTS, ..Default::default() }
+}
+
+fn float(name: &str, value: f64) -> AttributeProto {
+    AttributeProto { name: name.to_string(), f: Some(value as f32), r#type: ATTRIBUTE_FLOAT, ..Default::default() }
+}
+
+fn node(name: &str, op_type: &str, input: Vec<String>, attribute: Vec<AttributeProto>) -> NodeProto {
+    NodeProto { input, output: vec![name.to_string()], name: name.to_string(), op_type: op_type.to_string(), attribute }
+}
+
+fn tensor(name: String, xs: &Tensor) -> Result<TensorProto, TchError> {
+    let values = Vec::<f32>::try_from(&xs.f_flatten(0, -1)?)?;
+    Ok(TensorProto {
+        dims: xs.size(),
+        data_type: DATA_FLOAT,
+        name,
+        raw_data: values.iter().flat_map(|value| value.to_le_bytes()).collect(),
+        ..Default::default()
+    })
+}
+
+fn value_info(name: String, shape: &Shape) -> ValueInfoProto {
+    let batch = Dimension { dim_param: Some(BATCH_DIM.to_string()), ..Default::default() };
+    let dim = std::iter::once(batch)
+        .chain(shape.0.iter().map(|size| Dimension { dim_value: Some(*size), ..Default::default() }))
+        .collect();
+    let tensor_type = TensorTypeProto { elem_type: DATA_FLOAT, shape: Some(TensorShapeProto { dim }) };
+    ValueInfoProto { name, r#type: Some(TypeProto { tensor_type: Some(tensor_type) }) }
+}
+
+struct Block {
+    nodes: Vec<NodeProto>,
+    initializers: Vec<TensorProto>,
+}
+
+// the nodes computing a block, the last one outputs the value under the block name
+fn block(prepared: &Prepared, module: &GraphModule, vertex: &Vertex) -> Result<Block, TchError> {
+    let name = prepared.name(vertex.id);
+    let mut inputs = match vertex.kind {
+        LayerKind::Input => vec![GRAPH_INPUT.to_string()],
+        _ => prepared.inputs(vertex.id).into_iter().map(|id| prepared.name(id)).collect(),
+    };
+    let mut initializers = Vec::new();
+    for (parameter, xs) in module.parameters(vertex.id) {
+        let tensor_name = format!("{name}.{parameter}");
+        inputs.push(tensor_name.clone());
+        initializers.push(tensor(tensor_name, xs)?);
+    }
+    let param = |name: &str| vertex.params.int(name).unwrap_or_default();
+    let window = || {
+        vec![
+            ints("kernel_shape", vec![param("kernel_size"); 2]),
+            ints("strides", vec![param("stride"); 2]),
+            ints("pads", vec![param("padding"); 4]),
+        ]
+    };
+    let rank = prepared.shapes.get(&vertex.id).map_or(1, |shape| shape.0.len());
+    let node = match vertex.kind {
+        // Gemm only takes matrices, batches of larger samples are multiplied by the transposed weight
+        LayerKind::Linear if rank > 1 => {
+            let weight = module.parameters(vertex.id)[0].1.f_tr()?.f_contiguous()?;
+            let product = format!("{name}.matmul");
+            initializers[0] = tensor(format!("{name}.weight_t"), &weight)?;
+            let mut nodes = vec![node(&product, "MatMul", vec![inputs[0].clone(), initializers[0].name.clone()], vec![])];
+            nodes.push(match inputs.get(2) {
+                Some(bias) => node(&name, "Add", vec![product, bias.clone()], vec![]),
+                None => node(&name, "Identity", vec![product], vec![]),
+            });
+            return Ok(Block { nodes, initializers });
+        }
+        LayerKind::Linear => node(&name, "Gemm", inputs, vec![int("transB", 1)]),
+        LayerKind::Conv2d => node(&name, "Conv", inputs, window()),
+        LayerKind::MaxPool2d => node(&name, "MaxPool", inputs, window()),
+        // onnx keeps the share of the old running value
+        LayerKind::BatchNorm => node(&name, "BatchNormalization", inputs, vec![
+            float("epsilon", BATCH_NORM_EPS),
+            float("momentum", 1. - BATCH_NORM_MOMENTUM),
+        ]),
+        LayerKind::ReLU => node(&name, "Relu", inputs, vec![]),
+        LayerKind::LeakyReLU => node(&name, "LeakyRelu", inputs, vec![float("alpha", vertex.params.float("negative_slope").unwrap_or_default())]),
+        // the model is exported for inference, where dropout passes the values through
+        LayerKind::Dropout => node(&name, "Dropout", inputs, vec![]),
+        LayerKind::Flatten => node(&name, "Flatten", inputs, vec![int("axis", 1)]),
+        LayerKind::Softmax => node(&name, "Softmax", inputs, vec![int("axis", param("dim"))]),
+        LayerKind::Add => node(&name, "Add", inputs, vec![]),
+        LayerKind::Concat => node(&name, "Concat", inputs, vec![int("axis", param("dim"))]),
+        LayerKind::Input
+        | LayerKind::Identity
+        | LayerKind::Group
+        | LayerKind::GroupInput
+        | LayerKind::GroupOutput
+        | LayerKind::Output => node(&name, "Identity", inputs, vec![]),
+        // shape inference stops on them when the export is prepared
+        LayerKind::Op => unreachable!(),
+    };
+    Ok(Block { nodes: vec![node], initializers })
+}
+
+pub fn generate(prepared: &Prepared, module: &GraphModule) -> Result<Vec<u8>, api::Error> {
+    let mut graph = GraphProto { name: "nngraphix".to_string(), ..Default::default() };
+    for vertex in prepared.vertices() {
+        let block = block(prepared, module, vertex).map_err(|error| api::Error::at_block(prepared.flat.top_level(vertex.id), error))?;
+        graph.node.extend(block.nodes);
+        graph.initializer.extend(block.initializers);
+        let info = value_info(prepared.name(vertex.id), &prepared.shapes[&vertex.id]);
+        match vertex.id == prepared.output {
+            true => graph.output.push(info),
+            false => graph.value_info.push(info),
+        }
+    }
+    graph.input.push(value_info(GRAPH_INPUT.to_string(), &prepared.shapes[&prepared.input]));
+    let model = ModelProto {
+        ir_version: IR_VERSION,
+        producer_name: "NNGraphix".to_string(),
+        producer_version: env!("CARGO_PKG_VERSION").to_string(),
+        graph: Some(graph),
+        opset_import: vec![OperatorSetIdProto { domain: String::new(), version: OPSET_VERSION }],
+    };
+    Ok(model.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use common::{graph::{Edge, Graph, Vertex}, params::{ParamValue, Params}, Id};
+
+    use super::*;
+
+    fn vertex(id: Id, kind: LayerKind, values: &[(&str, ParamValue)]) -> Vertex {
+        let mut params = Params::defaults(kind);
+        values.iter().for_each(|(name, value)| params.set(name, value.clone()));
+        Vertex::new(id, kind, params)
+    }
+
+    // Input -> Conv2d -> ReLU -> Add -> Flatten -> Linear -> Output, with the Conv2d output also going into the Add
+    fn graph() -> Graph {
+        Graph {
+            vertices: vec![
+                vertex(1, LayerKind::Input, &[("shape", ParamValue::Dims(vec![3, 8, 8]))]),
+                vertex(2, LayerKind::Conv2d, &[
+                    ("in_channels", ParamValue::Int(3)),
+                    ("out_channels", ParamValue::Int(4)),
+                    ("kernel_size", ParamValue::Int(3)),
+                    ("stride", ParamValue::Int(1)),
+                    ("padding", ParamValue::Int(1)),
+                ]),
+                vertex(3, LayerKind::ReLU, &[]),
+                vertex(4, LayerKind::Add, &[]),
+                vertex(5, LayerKind::Flatten, &[]),
+                vertex(6, LayerKind::Linear, &[("in_features", ParamValue::Int(256)), ("out_features", ParamValue::Int(10))]),
+                vertex(7, LayerKind::Output, &[]),
+            ],
+            edges: vec![
+                Edge::new(1, 0, 2, 0),
+                Edge::new(2, 0, 3, 0),
+                Edge::new(2, 0, 4, 0),
+                Edge::new(3, 0, 4, 1),
+                Edge::new(4, 0, 5, 0),
+                Edge::new(5, 0, 6, 0),
+                Edge::new(6, 0, 7, 0),
+            ],
+        }
+    }
+
+    #[test]
+    fn generate_round_trips_the_graph() {
+        let graph = graph();
+        let prepared = Prepared::new(&graph).unwrap();
+        let bytes = generate(&prepared, &GraphModule::build(&graph).unwrap()).unwrap();
+        let model = ModelProto::decode(bytes.as_slice()).unwrap();
+        let onnx = model.graph.unwrap();
+        // a node for every block, an input of those nodes for every arrow
+        let blocks = graph.vertices.iter().map(|vertex| prepared.name(vertex.id)).collect::<BTreeSet<_>>();
+        let nodes = onnx.node.iter().filter(|node| node.output.iter().any(|output| blocks.contains(output))).count();
+        let edges = onnx.node.iter().flat_map(|node| &node.input).filter(|input| blocks.contains(*input)).count();
+        assert_eq!(nodes, graph.vertices.len());
+        assert_eq!(edges, graph.edges.len());
+        let op_types = onnx.node.iter().map(|node| node.op_type.as_str()).collect::<Vec<_>>();
+        assert_eq!(op_types, ["Identity", "Conv", "Relu", "Add", "Flatten", "Gemm", "Identity"]);
+        // the Conv2d and Linear weights and biases
+        assert_eq!(onnx.initializer.len(), 4);
+        assert_eq!(onnx.output[0].name, prepared.name(7));
+    }
+}
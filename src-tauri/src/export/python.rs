@@ -0,0 +1,86 @@
+use std::fmt::Write;
+
+use common::{graph::Vertex, layer::LayerKind};
+
+use super::Prepared;
+
+const CLASS_NAME: &str = "Model";
+
+// the `nn` module made in `__init__`, None for blocks computed right in `forward`
+fn module(prepared: &Prepared, vertex: &Vertex) -> Option<String> {
+    let int = |name: &str| vertex.params.int(name).unwrap_or_default();
+    let float = |name: &str| vertex.params.float(name).unwrap_or_default();
+    let module = match vertex.kind {
+        LayerKind::Linear => format!("nn.Linear({}, {})", int("in_features"), int("out_features")),
+        LayerKind::Conv2d => format!(
+            "nn.Conv2d({}, {}, kernel_size={}, stride={}, padding={})",
+            int("in_channels"),
+            int("out_channels"),
+            int("kernel_size"),
+            int("stride"),
+            int("padding"),
+        ),
+        LayerKind::MaxPool2d => {
+            format!("nn.MaxPool2d(kernel_size={}, stride={}, padding={})", int("kernel_size"), int("stride"), int("padding"))
+        }
+        // samples of (features, height, width) are normalized per channel over the image
+        LayerKind::BatchNorm => {
+            let rank = prepared.shapes.get(&vertex.id).map_or(1, |shape| shape.0.len());
+            let dims = if rank == 3 { "2d" } else { "1d" };
+            format!("nn.BatchNorm{dims}({})", int("num_features"))
+        }
+        LayerKind::ReLU => "nn.ReLU()".to_string(),
+        LayerKind::LeakyReLU => format!("nn.LeakyReLU(negative_slope={:?})", float("negative_slope")),
+        LayerKind::Dropout => format!("nn.Dropout(p={:?})", float("p")),
+        LayerKind::Flatten => "nn.Flatten()".to_string(),
+        LayerKind::Softmax => format!("nn.Softmax(dim={})", int("dim")),
+        _ => return None,
+    };
+    Some(module)
+}
+
+// the right hand side of the assignment in `forward`
+fn expression(prepared: &Prepared, vertex: &Vertex) -> String {
+    let inputs = prepared.inputs(vertex.id).into_iter().map(|id| prepared.name(id)).collect::<Vec<_>>();
+    match vertex.kind {
+        LayerKind::Input => "x".to_string(),
+        LayerKind::Add => inputs.join(" + "),
+        LayerKind::Concat => format!("torch.cat([{}], dim={})", inputs.join(", "), vertex.params.int("dim").unwrap_or_default()),
+        _ if module(prepared, vertex).is_some() => format!("self.{}({})", prepared.name(vertex.id), inputs.join(", ")),
+        // identities and the Output block just pass the value on
+        _ => inputs.join(", "),
+    }
+}
+
+// python source of a `torch.nn.Module` computing the same as the board
+pub fn generate(prepared: &Prepared) -> String {
+    let input_shape = prepared.shapes[&prepared.input].0.iter().map(i64::to_string).collect::<Vec<_>>().join(", ");
+    let mut source = String::new();
+    // writing to a string does not fail
+    let _ = writeln!(source, "# generated by NNGraphix");
+    let _ = writeln!(source, "import torch");
+    let _ = writeln!(source, "from torch import nn");
+    let _ = writeln!(source);
+    let _ = writeln!(source);
+    let _ = writeln!(source, "class {CLASS_NAME}(nn.Module):");
+    let _ = writeln!(source, "    # expects a batch of samples of shape ({input_shape})");
+    let _ = writeln!(source, "    def __init__(self):");
+    let _ = writeln!(source, "        super().__init__()");
+    prepared.vertices().for_each(|vertex| {
+        if let Some(module) = module(prepared, vertex) {
+            let _ = writeln!(source, "        self.{} = {module}", prepared.name(vertex.id));
+        }
+    });
+    let _ = writeln!(source);
+    let _ = writeln!(source, "    def forward(self, x):");
+    prepared.vertices().for_each(|vertex| {
+        let _ = writeln!(source, "        {} = {}", prepared.name(vertex.id), expression(prepared, vertex));
+    });
+    let _ = writeln!(source, "        return {}", prepared.name(prepared.output));
+    let _ = writeln!(source);
+    let _ = writeln!(source);
+    let _ = writeln!(source, "if __name__ == \"__main__\":");
+    let _ = writeln!(source, "    model = {CLASS_NAME}().eval()");
+    let _ = writeln!(source, "    print(model(torch.randn(1, {input_shape})).shape)");
+    source
+}
@@ -0,0 +1,35 @@
+mod onnx;
+
+use std::{fs, path::Path};
+
+use common::{api, graph::Graph, layout, project::{self, Block, Project}, Id};
+
+// the imported blocks are laid out in columns along the arrows, the board opens at the left end of the graph
+fn project(graph: Graph, last_id: Id) -> Project {
+    let ids = graph.vertices.iter().map(|vertex| vertex.id).collect::<Vec<_>>();
+    let centers = layout::layered(&ids, &graph.edges);
+    let blocks = graph
+        .vertices
+        .into_iter()
+        .map(|vertex| {
+            let (x, y) = centers[&vertex.id];
+            Block { id: vertex.id, kind: vertex.kind, params: vertex.params, x, y, group: None, component: None }
+        })
+        .collect::<Vec<_>>();
+    let top = blocks.iter().map(|block| block.y).fold(0., f64::min);
+    let viewbox = project::Viewbox { x: -layout::COLUMN_SPACING, y: top - layout::ROW_SPACING, scale: 1. };
+    Project { version: project::VERSION, last_id, viewbox, blocks, edges: graph.edges }
+}
+
+// the format is picked by the file extension
+pub fn import(path: &Path) -> Result<Project, api::Error> {
+    let bytes = fs::read(path).map_err(|error| api::Error::new(format!("cannot read {}: {error}", path.display())))?;
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    let (graph, last_id) = match extension {
+        "onnx" => onnx::parse(&bytes)?,
+        _ => return Err(api::Error::new(format!("cannot import \"{}\", expected an .onnx file", path.display()))),
+    };
+    let project = project(graph, last_id);
+    project.validate()?;
+    Ok(project)
+}
@@ -0,0 +1,349 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{
+    api,
+    graph::{Edge, Graph, Vertex},
+    layer::LayerKind,
+    params::{self, ParamValue, Params},
+    Id,
+};
+use prost::Message;
+
+use crate::proto::*;
+
+// softmax flattened the input to a matrix at the axis before, since it works along that axis alone as torch does
+const SOFTMAX_SINGLE_AXIS_OPSET: i64 = 13;
+
+fn attribute<'a>(node: &'a NodeProto, name: &str) -> Option<&'a AttributeProto> {
+    node.attribute.iter().find(|attribute| attribute.name == name)
+}
+
+fn int(node: &NodeProto, name: &str) -> Option<i64> {
+    attribute(node, name).and_then(|attribute| attribute.i)
+}
+
+fn float(node: &NodeProto, name: &str) -> Option<f64> {
+    attribute(node, name).and_then(|attribute| attribute.f).map(f64::from)
+}
+
+fn ints<'a>(node: &'a NodeProto, name: &str) -> Option<&'a [i64]> {
+    attribute(node, name).map(|attribute| attribute.ints.as_slice())
+}
+
+// a single value for all the spatial dimensions, as the board layers take it
+fn same(values: Option<&[i64]>, default: i64) -> Option<i64> {
+    match values {
+        None | Some([]) => Some(default),
+        Some([first, rest @ ..]) => rest.iter().all(|value| value == first).then_some(*first),
+    }
+}
+
+// "NOTSET" or no auto_pad at all means the pads are given explicitly
+fn explicit_pads(node: &NodeProto) -> bool {
+    attribute(node, "auto_pad").and_then(|pad| pad.s.as_deref()).is_none_or(|pad| pad == b"NOTSET")
+}
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+// attributes of the other types, like tensors and subgraphs, are not shown
+fn attribute_value(attribute: &AttributeProto) -> Option<ParamValue> {
+    let list = |values: Vec<String>| ParamValue::Text(values.join(", "));
+    match attribute.r#type {
+        ATTRIBUTE_FLOAT => attribute.f.map(|value| ParamValue::Float(value.into())),
+        ATTRIBUTE_INT => attribute.i.map(ParamValue::Int),
+        ATTRIBUTE_STRING => attribute.s.as_deref().map(|value| ParamValue::Text(string(value))),
+        ATTRIBUTE_FLOATS => Some(list(attribute.floats.iter().map(f32::to_string).collect())),
+        ATTRIBUTE_INTS => Some(ParamValue::Dims(attribute.ints.clone())),
+        ATTRIBUTE_STRINGS => Some(list(attribute.strings.iter().map(|value| string(value)).collect())),
+        _ => None,
+    }
+}
+
+fn scalar(tensor: &TensorProto) -> Option<f64> {
+    let raw = tensor.raw_data.get(..4).and_then(|bytes| bytes.try_into().ok()).map(f32::from_le_bytes);
+    raw.or(tensor.float_data.first().copied()).filter(|_| tensor.data_type == DATA_FLOAT).map(f64::from)
+}
+
+// the sample shape of a graph input, without the batch dimension
+fn sample_shape(info: &ValueInfoProto) -> Option<Vec<i64>> {
+    let shape = info.r#type.as_ref()?.tensor_type.as_ref()?.shape.as_ref()?;
+    let dims = shape.dim.iter().skip(1).map(|dim| dim.dim_value.filter(|size| *size > 0)).collect::<Option<Vec<_>>>()?;
+    (!dims.is_empty()).then_some(dims)
+}
+
+struct Importer<'a> {
+    graph: Graph,
+    last_id: Id,
+    initializers: BTreeMap<&'a str, &'a TensorProto>,
+    // the block output computing every named value
+    values: BTreeMap<&'a str, (Id, usize)>,
+    // Linear blocks made of a MatMul, the Add of the bias after them is merged in
+    matmuls: BTreeSet<Id>,
+    opset: i64,
+}
+
+impl<'a> Importer<'a> {
+    fn add_vertex(&mut self, kind: LayerKind, params: Params) -> Id {
+        self.last_id += 1;
+        self.graph.vertices.push(Vertex::new(self.last_id, kind, params));
+        self.last_id
+    }
+
+    fn weight_dims(&self, node: &NodeProto, index: usize) -> Option<&'a [i64]> {
+        let name = node.input.get(index)?;
+        self.initializers.get(name.as_str()).map(|tensor| tensor.dims.as_slice())
+    }
+
+    // the matching board layer, None when some attribute has no counterpart in it
+    fn layer(&self, node: &NodeProto, inputs: usize) -> Option<(LayerKind, Vec<(&'static str, ParamValue)>)> {
+        let single = inputs == 1;
+        let layer = match node.op_type.as_str() {
+            "Gemm" if single && int(node, "transA").unwrap_or(0) == 0 => {
+                let unit = |name| float(node, name).unwrap_or(1.) == 1.;
+                if !unit("alpha") || !unit("beta") {
+                    return None;
+                }
+                let &[rows, columns] = self.weight_dims(node, 1)? else {
+                    return None;
+                };
+                let (in_features, out_features) = match int(node, "transB").unwrap_or(0) {
+                    0 => (rows, columns),
+                    _ => (columns, rows),
+                };
+                (LayerKind::Linear, vec![("in_features", ParamValue::Int(in_features)), ("out_features", ParamValue::Int(out_features))])
+            }
+            "MatMul" if single => {
+                let &[in_features, out_features] = self.weight_dims(node, 1)? else {
+                    return None;
+                };
+                (LayerKind::Linear, vec![("in_features", ParamValue::Int(in_features)), ("out_features", ParamValue::Int(out_features))])
+            }
+            "Conv" if single => {
+                let &[out_channels, in_channels, height, width] = self.weight_dims(node, 1)? else {
+                    return None;
+                };
+                let plain = int(node, "group").unwrap_or(1) == 1 && same(ints(node, "dilations"), 1)? == 1;
+                if height != width || !plain || !explicit_pads(node) {
+                    return None;
+                }
+                (LayerKind::Conv2d, vec![
+                    ("in_channels", ParamValue::Int(in_channels)),
+                    ("out_channels", ParamValue::Int(out_channels)),
+                    ("kernel_size", ParamValue::Int(height)),
+                    ("stride", ParamValue::Int(same(ints(node, "strides"), 1)?)),
+                    ("padding", ParamValue::Int(same(ints(node, "pads"), 0)?)),
+                ])
+            }
+            "MaxPool" if single => {
+                let kernel = ints(node, "kernel_shape").filter(|kernel| kernel.len() == 2)?;
+                let plain = int(node, "ceil_mode").unwrap_or(0) == 0 && same(ints(node, "dilations"), 1)? == 1;
+                if !plain || !explicit_pads(node) {
+                    return None;
+                }
+                (LayerKind::MaxPool2d, vec![
+                    ("kernel_size", ParamValue::Int(same(Some(kernel), 1)?)),
+                    ("stride", ParamValue::Int(same(ints(node, "strides"), 1)?)),
+                    ("padding", ParamValue::Int(same(ints(node, "pads"), 0)?)),
+                ])
+            }
+            "BatchNormalization" if single => {
+                let &[features] = self.weight_dims(node, 1)? else {
+                    return None;
+                };
+                (LayerKind::BatchNorm, vec![("num_features", ParamValue::Int(features))])
+            }
+            "Relu" if single => (LayerKind::ReLU, vec![]),
+            "LeakyRelu" if single => (LayerKind::LeakyReLU, vec![("negative_slope", ParamValue::Float(float(node, "alpha").unwrap_or(0.01)))]),
+            // the ratio became an input in opset 12
+            "Dropout" if single => {
+                let input = node.input.get(1).and_then(|name| self.initializers.get(name.as_str())).and_then(|tensor| scalar(tensor));
+                (LayerKind::Dropout, vec![("p", ParamValue::Float(float(node, "ratio").or(input).unwrap_or(0.5)))])
+            }
+            "Flatten" if single && int(node, "axis").unwrap_or(1) == 1 => (LayerKind::Flatten, vec![]),
+            "Softmax" if single && self.opset >= SOFTMAX_SINGLE_AXIS_OPSET => {
+                (LayerKind::Softmax, vec![("dim", ParamValue::Int(int(node, "axis").unwrap_or(-1)))])
+            }
+            "Add" if inputs == 2 => (LayerKind::Add, vec![]),
+            "Concat" if inputs == 2 => (LayerKind::Concat, vec![("dim", ParamValue::Int(int(node, "axis").unwrap_or(1)))]),
+            _ => return None,
+        };
+        Some(layer)
+    }
+
+    fn add_node(&mut self, node: &'a NodeProto) -> Result<(), api::Error> {
+        let mut sources = Vec::new();
+        for name in node.input.iter().filter(|name| !name.is_empty()) {
+            match (self.values.get(name.as_str()), self.initializers.contains_key(name.as_str())) {
+                (Some(source), _) => sources.push(*source),
+                (None, true) => {}
+                (None, false) => return Err(api::Error::new(format!("node \"{}\" uses an unknown value \"{name}\"", node.name))),
+            }
+        }
+        let outputs = node.output.iter().filter(|name| !name.is_empty()).count();
+        let output = node.output.first().map(String::as_str).unwrap_or_default();
+        // identities and the bias of a MatMul made into a Linear block add nothing to the board
+        match (node.op_type.as_str(), sources.as_slice()) {
+            ("Identity", &[source]) => {
+                self.values.insert(output, source);
+                return Ok(());
+            }
+            ("Add", &[source]) if self.matmuls.remove(&source.0) => {
+                self.values.insert(output, source);
+                return Ok(());
+            }
+            _ => {}
+        }
+        let id = match self.layer(node, sources.len()).filter(|_| outputs == 1) {
+            Some((kind, values)) => {
+                let mut params = Params::defaults(kind);
+                values.into_iter().for_each(|(name, value)| params.set(name, value));
+                let id = self.add_vertex(kind, params);
+                if node.op_type == "MatMul" {
+                    self.matmuls.insert(id);
+                }
+                id
+            }
+            None => {
+                let mut params = Params::defaults(LayerKind::Op);
+                node.attribute.iter().for_each(|attribute| {
+                    if let Some(value) = attribute_value(attribute) {
+                        params.set(&attribute.name, value);
+                    }
+                });
+                params.set(params::OP_TYPE, ParamValue::Text(node.op_type.clone()));
+                params.set(params::OP_INPUTS, ParamValue::Int(sources.len() as i64));
+                params.set(params::OP_OUTPUTS, ParamValue::Int(node.output.len() as i64));
+                self.add_vertex(LayerKind::Op, params)
+            }
+        };
+        for (port, (from, from_port)) in sources.into_iter().enumerate() {
+            self.graph.edges.push(Edge::new(from, from_port, id, port));
+        }
+        for (port, name) in node.output.iter().enumerate().filter(|(_, name)| !name.is_empty()) {
+            self.values.insert(name, (id, port));
+        }
+        Ok(())
+    }
+}
+
+// a block for every node, Input and Output blocks for the inputs and outputs of the model
+pub fn parse(bytes: &[u8]) -> Result<(Graph, Id), api::Error> {
+    let model = ModelProto::decode(bytes).map_err(|error| api::Error::new(format!("not an onnx model: {error}")))?;
+    let graph = model.graph.as_ref().ok_or_else(|| api::Error::new("the onnx model has no graph"))?;
+    let opset = model
+        .opset_import
+        .iter()
+        .find(|opset| opset.domain.is_empty() || opset.domain == "ai.onnx")
+        .map_or(1, |opset| opset.version);
+    let mut importer = Importer {
+        graph: Graph::default(),
+        last_id: 0,
+        initializers: graph.initializer.iter().map(|tensor| (tensor.name.as_str(), tensor)).collect(),
+        values: BTreeMap::new(),
+        matmuls: BTreeSet::new(),
+        opset,
+    };
+    // older writers list the initializers among the inputs too
+    let inputs = graph.input.iter().filter(|input| !importer.initializers.contains_key(input.name.as_str())).collect::<Vec<_>>();
+    for input in inputs {
+        let mut params = Params::defaults(LayerKind::Input);
+        if let Some(shape) = sample_shape(input) {
+            params.set("shape", ParamValue::Dims(shape));
+        }
+        let id = importer.add_vertex(LayerKind::Input, params);
+        importer.values.insert(&input.name, (id, 0));
+    }
+    // nodes are stored in topological order
+    for node in &graph.node {
+        importer.add_node(node)?;
+    }
+    for output in &graph.output {
+        let Some(&(from, from_port)) = importer.values.get(output.name.as_str()) else {
+            return Err(api::Error::new(format!("the model output \"{}\" is not computed by any node", output.name)));
+        };
+        let id = importer.add_vertex(LayerKind::Output, Params::defaults(LayerKind::Output));
+        importer.graph.edges.push(Edge::new(from, from_port, id, 0));
+    }
+    Ok((importer.graph, importer.last_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(op_type: &str, input: &[&str], output: &str, attribute: Vec<AttributeProto>) -> NodeProto {
+        NodeProto {
+            input: input.iter().map(|name| name.to_string()).collect(),
+            output: vec![output.to_string()],
+            name: output.to_string(),
+            op_type: op_type.to_string(),
+            attribute,
+        }
+    }
+
+    fn weight(name: &str, dims: Vec<i64>) -> TensorProto {
+        let count = dims.iter().product::<i64>() as usize;
+        TensorProto { dims, data_type: DATA_FLOAT, float_data: vec![0.; count], name: name.to_string(), ..Default::default() }
+    }
+
+    fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+        let dim = dims.iter().map(|size| Dimension { dim_value: Some(*size), dim_param: None }).collect();
+        let tensor_type = TensorTypeProto { elem_type: DATA_FLOAT, shape: Some(TensorShapeProto { dim }) };
+        ValueInfoProto { name: name.to_string(), r#type: Some(TypeProto { tensor_type: Some(tensor_type) }) }
+    }
+
+    fn model(nodes: Vec<NodeProto>, opset: i64) -> Vec<u8> {
+        let graph = GraphProto {
+            node: nodes,
+            initializer: vec![weight("w", vec![3, 4]), weight("b", vec![3])],
+            input: vec![value_info("x", &[1, 4])],
+            output: vec![value_info("y", &[1, 3])],
+            ..Default::default()
+        };
+        let opset_import = vec![OperatorSetIdProto { domain: String::new(), version: opset }];
+        ModelProto { graph: Some(graph), opset_import, ..Default::default() }.encode_to_vec()
+    }
+
+    fn kinds(graph: &Graph) -> Vec<LayerKind> {
+        graph.vertices.iter().map(|vertex| vertex.kind).collect()
+    }
+
+    #[test]
+    fn parse_makes_layers_and_op_blocks() {
+        let attributes = vec![
+            AttributeProto { name: "mode".to_string(), s: Some(b"fast".to_vec()), r#type: ATTRIBUTE_STRING, ..Default::default() },
+            AttributeProto { name: "k".to_string(), i: Some(2), r#type: ATTRIBUTE_INT, ..Default::default() },
+        ];
+        let bytes = model(vec![
+            node("Gemm", &["x", "w", "b"], "h", vec![AttributeProto { name: "transB".to_string(), i: Some(1), r#type: ATTRIBUTE_INT, ..Default::default() }]),
+            node("Relu", &["h"], "r", vec![]),
+            node("Unheard", &["r"], "y", attributes),
+        ], 13);
+        let (graph, last_id) = parse(&bytes).unwrap();
+        assert_eq!(kinds(&graph), [LayerKind::Input, LayerKind::Linear, LayerKind::ReLU, LayerKind::Op, LayerKind::Output]);
+        assert_eq!(last_id, 5);
+        assert_eq!(graph.vertices[0].params.dims("shape"), Some([4].as_slice()));
+        assert_eq!(graph.vertices[1].params.int("in_features"), Some(4));
+        assert_eq!(graph.vertices[1].params.int("out_features"), Some(3));
+        let op = &graph.vertices[3].params;
+        assert_eq!(op.text(params::OP_TYPE), Some("Unheard"));
+        assert_eq!(op.text("mode"), Some("fast"));
+        assert_eq!(op.int("k"), Some(2));
+        assert_eq!(op.int(params::OP_INPUTS), Some(1));
+        assert_eq!(op.int(params::OP_OUTPUTS), Some(1));
+        let edges = (1..5).map(|id| Edge::new(id, 0, id + 1, 0)).collect::<Vec<_>>();
+        assert_eq!(graph.edges, edges);
+    }
+
+    #[test]
+    fn parse_keeps_old_softmax_as_op() {
+        let nodes = || vec![node("Softmax", &["x"], "y", vec![])];
+        let (graph, _) = parse(&model(nodes(), 11)).unwrap();
+        assert_eq!(kinds(&graph), [LayerKind::Input, LayerKind::Op, LayerKind::Output]);
+        let (graph, _) = parse(&model(nodes(), 13)).unwrap();
+        assert_eq!(kinds(&graph), [LayerKind::Input, LayerKind::Softmax, LayerKind::Output]);
+        assert_eq!(graph.vertices[1].params.int("dim"), Some(-1));
+    }
+}
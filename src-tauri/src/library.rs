@@ -0,0 +1,69 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use common::{api, library::{self, Component, ComponentRef}, project::Group};
+use tauri::{AppHandle, Manager};
+
+// components stored as json files in the `library` directory of the app data
+pub struct Library {
+    dir: PathBuf,
+}
+
+impl Library {
+    pub fn open(app: &AppHandle) -> Result<Self, api::Error> {
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|error| api::Error::new(format!("no app data directory: {error}")))?
+            .join("library");
+        fs::create_dir_all(&dir).map_err(|error| api::Error::new(format!("cannot create {}: {error}", dir.display())))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    // every readable component by name, files that do not parse are skipped
+    pub fn components(&self) -> Result<BTreeMap<String, Component>, api::Error> {
+        let entries = fs::read_dir(&self.dir).map_err(|error| api::Error::new(format!("cannot read {}: {error}", self.dir.display())))?;
+        Ok(entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .filter_map(|path| serde_json::from_str::<Component>(&fs::read_to_string(path).ok()?).ok())
+            .map(|component| (component.name.clone(), component))
+            .collect())
+    }
+
+    pub fn list(&self) -> Result<Vec<ComponentRef>, api::Error> {
+        Ok(self.components()?.values().map(Component::reference).collect())
+    }
+
+    pub fn load(&self, name: &str) -> Result<Component, api::Error> {
+        if !library::is_valid_name(name) {
+            return Err(api::Error::new(format!("invalid component name \"{name}\"")));
+        }
+        let path = self.path(name);
+        let json = fs::read_to_string(&path).map_err(|error| api::Error::new(format!("cannot read {}: {error}", path.display())))?;
+        let component = serde_json::from_str::<Component>(&json).map_err(api::Error::new)?;
+        component.group.validate()?;
+        Ok(component)
+    }
+
+    // saving under an existing name makes a new version of the component
+    pub fn save(&self, name: &str, group: Group) -> Result<ComponentRef, api::Error> {
+        if !library::is_valid_name(name) {
+            return Err(api::Error::new(format!("invalid component name \"{name}\"")));
+        }
+        group.validate()?;
+        let version = match self.path(name).exists() {
+            true => self.load(name)?.version + 1,
+            false => 1,
+        };
+        let component = Component { name: name.to_string(), version, group };
+        let json = serde_json::to_string_pretty(&component).map_err(api::Error::new)?;
+        let path = self.path(name);
+        fs::write(&path, json).map_err(|error| api::Error::new(format!("cannot write {}: {error}", path.display())))?;
+        Ok(component.reference())
+    }
+}
@@ -1,6 +1,15 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod commands;
+mod export;
+mod import;
+mod library;
+mod model;
+mod project;
+mod proto;
+mod torchscript;
+
 use tauri::{Manager, LogicalSize};
 
 fn main() {
@@ -19,6 +28,19 @@ fn main() {
 
       Ok(())
     }) 
+    .invoke_handler(tauri::generate_handler![
+      commands::compile,
+      commands::run_forward,
+      commands::export_model,
+      commands::import_model,
+      commands::save_torchscript,
+      commands::run_torchscript,
+      commands::save_project,
+      commands::load_project,
+      commands::list_components,
+      commands::load_component,
+      commands::save_component,
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
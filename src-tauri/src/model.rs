@@ -0,0 +1,301 @@
+use std::{collections::BTreeMap, path::Path};
+
+use common::{api, graph::Graph, group::Flat, layer::LayerKind, params::Params, Id};
+use tch::{nn, Device, Kind, TchError, Tensor};
+
+pub const BATCH_NORM_MOMENTUM: f64 = 0.1;
+pub const BATCH_NORM_EPS: f64 = 1e-5;
+
+enum Layer {
+    Input,
+    Linear(nn::Linear),
+    Conv2d { conv: nn::Conv2D, stride: i64, padding: i64 },
+    MaxPool2d { kernel: i64, stride: i64, padding: i64 },
+    BatchNorm(nn::BatchNorm),
+    ReLU,
+    LeakyReLU(f64),
+    Dropout(f64),
+    Flatten,
+    Softmax(i64),
+    Add,
+    Concat(i64),
+    Identity,
+}
+
+impl Layer {
+    fn new(path: nn::Path, kind: LayerKind, params: &Params) -> Self {
+        let int = |name: &str| params.int(name).unwrap_or_default();
+        let float = |name: &str| params.float(name).unwrap_or_default();
+        match kind {
+            LayerKind::Input => Self::Input,
+            LayerKind::Linear => Self::Linear(nn::linear(path, int("in_features"), int("out_features"), Default::default())),
+            LayerKind::Conv2d => Self::Conv2d {
+                conv: nn::conv2d(path, int("in_channels"), int("out_channels"), int("kernel_size"), Default::default()),
+                stride: int("stride"),
+                padding: int("padding"),
+            },
+            LayerKind::MaxPool2d => Self::MaxPool2d { kernel: int("kernel_size"), stride: int("stride"), padding: int("padding") },
+            LayerKind::BatchNorm => Self::BatchNorm(nn::batch_norm1d(path, int("num_features"), Default::default())),
+            LayerKind::ReLU => Self::ReLU,
+            LayerKind::LeakyReLU => Self::LeakyReLU(float("negative_slope")),
+            LayerKind::Dropout => Self::Dropout(float("p")),
+            LayerKind::Flatten => Self::Flatten,
+            LayerKind::Softmax => Self::Softmax(int("dim")),
+            LayerKind::Add => Self::Add,
+            LayerKind::Concat => Self::Concat(int("dim")),
+            // groups are flattened and Op blocks fail shape inference before the model is built
+            LayerKind::Identity
+            | LayerKind::Op
+            | LayerKind::Group
+            | LayerKind::GroupInput
+            | LayerKind::GroupOutput
+            | LayerKind::Output => Self::Identity,
+        }
+    }
+
+    fn param_count(&self) -> i64 {
+        let count = |ws: &Tensor, bs: Option<&Tensor>| (ws.numel() + bs.map_or(0, |bs| bs.numel())) as i64;
+        match self {
+            Self::Linear(linear) => count(&linear.ws, linear.bs.as_ref()),
+            Self::Conv2d { conv, .. } => count(&conv.ws, conv.bs.as_ref()),
+            Self::BatchNorm(norm) => norm.ws.as_ref().map_or(0, |ws| count(ws, norm.bs.as_ref())),
+            _ => 0,
+        }
+    }
+
+    // tensors of the layer under the names torch gives them
+    fn parameters(&self) -> Vec<(&'static str, &Tensor)> {
+        fn weights<'a>(ws: Option<&'a Tensor>, bs: Option<&'a Tensor>) -> Vec<(&'static str, &'a Tensor)> {
+            [ws.map(|ws| ("weight", ws)), bs.map(|bs| ("bias", bs))].into_iter().flatten().collect()
+        }
+        match self {
+            Self::Linear(linear) => weights(Some(&linear.ws), linear.bs.as_ref()),
+            Self::Conv2d { conv, .. } => weights(Some(&conv.ws), conv.bs.as_ref()),
+            Self::BatchNorm(norm) => {
+                let mut parameters = weights(norm.ws.as_ref(), norm.bs.as_ref());
+                parameters.extend([("running_mean", &norm.running_mean), ("running_var", &norm.running_var)]);
+                parameters
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn forward(&self, inputs: &[&Tensor], train: bool) -> Result<Tensor, TchError> {
+        let single = || match inputs {
+            [xs] => Ok(*xs),
+            _ => Err(TchError::Shape(format!("expected one input, got {}", inputs.len()))),
+        };
+        match self {
+            Self::Input | Self::Identity => Ok(single()?.shallow_clone()),
+            Self::Linear(linear) => single()?.f_linear(&linear.ws, linear.bs.as_ref()),
+            Self::Conv2d { conv, stride, padding } => {
+                single()?.f_conv2d(&conv.ws, conv.bs.as_ref(), [*stride, *stride], [*padding, *padding], [1, 1], 1)
+            }
+            Self::MaxPool2d { kernel, stride, padding } => {
+                single()?.f_max_pool2d([*kernel, *kernel], [*stride, *stride], [*padding, *padding], [1, 1], false)
+            }
+            Self::BatchNorm(norm) => single()?.f_batch_norm(
+                norm.ws.as_ref(),
+                norm.bs.as_ref(),
+                Some(&norm.running_mean),
+                Some(&norm.running_var),
+                train,
+                BATCH_NORM_MOMENTUM,
+                BATCH_NORM_EPS,
+                false,
+            ),
+            Self::ReLU => single()?.f_relu(),
+            // the slope is in [0, 1], so the maximum picks the right branch
+            Self::LeakyReLU(slope) => {
+                let xs = single()?;
+                xs.f_maximum(&xs.f_mul_scalar(*slope)?)
+            }
+            Self::Dropout(p) => single()?.f_dropout(*p, train),
+            Self::Flatten => single()?.f_flatten(1, -1),
+            Self::Softmax(dim) => single()?.f_softmax(*dim, Kind::Float),
+            Self::Add => inputs.iter().skip(1).try_fold(inputs[0].shallow_clone(), |sum, xs| sum.f_add(xs)),
+            Self::Concat(dim) => Tensor::f_cat(inputs, *dim),
+        }
+    }
+}
+
+struct Node {
+    id: Id,
+    kind: LayerKind,
+    inputs: Vec<Id>,
+    layer: Layer,
+}
+
+// the board graph built into a torch module, blocks are evaluated in topological order
+pub struct GraphModule {
+    // owns the parameters referenced by the layers
+    vs: nn::VarStore,
+    nodes: Vec<Node>,
+    input: Id,
+    // shape of a single sample fed to the Input block
+    input_shape: Vec<i64>,
+    output: Id,
+    // maps the flattened blocks back to the board
+    flat: Flat,
+    kinds: BTreeMap<Id, LayerKind>,
+}
+
+pub fn single_block(graph: &Graph, kind: LayerKind) -> Result<Id, api::Error> {
+    let mut blocks = graph.vertices.iter().filter(|vertex| vertex.kind == kind);
+    match (blocks.next(), blocks.next()) {
+        (Some(vertex), None) => Ok(vertex.id),
+        (None, _) => Err(api::Error::new(format!("the graph has no {kind} block"))),
+        (Some(_), Some(extra)) => Err(api::Error::at_block(extra.id, format!("the graph should have a single {kind} block"))),
+    }
+}
+
+// a batch for the given Input block taking samples of the given shape
+pub fn sample(block: Id, shape: &[i64], input: &api::SampleInput) -> Result<Tensor, api::Error> {
+    let error = |error| api::Error::at_block(block, error);
+    match input {
+        api::SampleInput::Random { batch } => {
+            let shape = [&[*batch][..], shape].concat();
+            Tensor::f_randn(shape, (Kind::Float, Device::Cpu)).map_err(error)
+        }
+        api::SampleInput::Values(values) => {
+            let expected = shape.iter().product::<i64>();
+            if values.len() as i64 != expected {
+                return Err(api::Error::at_block(block, format!("expected {expected} values, got {}", values.len())));
+            }
+            let shape = [&[1][..], shape].concat();
+            let values = values.iter().map(|value| *value as f32).collect::<Vec<_>>();
+            Tensor::f_from_slice(&values).and_then(|xs| xs.f_reshape(shape)).map_err(error)
+        }
+    }
+}
+
+fn stats(path: Vec<Id>, ys: &Tensor) -> Result<api::BlockStats, TchError> {
+    let ys = ys.f_to_kind(Kind::Double)?;
+    Ok(api::BlockStats {
+        path,
+        shape: ys.size(),
+        mean: ys.f_mean(Kind::Double)?.f_double_value(&[])?,
+        std: ys.f_std(false)?.f_double_value(&[])?,
+        min: ys.f_min()?.f_double_value(&[])?,
+        max: ys.f_max()?.f_double_value(&[])?,
+    })
+}
+
+impl GraphModule {
+    // groups are replaced by their contents, errors point at the blocks of the board
+    pub fn build(graph: &Graph) -> Result<Self, api::Error> {
+        graph.check_groups()?;
+        let flat = graph.flatten();
+        let kinds = graph.vertices.iter().map(|vertex| (vertex.id, vertex.kind)).collect();
+        Self::build_flat(flat, kinds)
+    }
+
+    fn build_flat(flat: Flat, kinds: BTreeMap<Id, LayerKind>) -> Result<Self, api::Error> {
+        let error = |error| flat.top_level_error(error);
+        let graph = &flat.graph;
+        let input = single_block(graph, LayerKind::Input).map_err(error)?;
+        let output = single_block(graph, LayerKind::Output).map_err(error)?;
+        // reports shape mismatches and unconnected blocks before torch does
+        let input_shape = graph.infer_shapes().map_err(error)?.remove(&input).unwrap().0;
+        let vs = nn::VarStore::new(Device::Cpu);
+        let nodes = graph
+            .topological_order()
+            .map_err(error)?
+            .into_iter()
+            .map(|vertex| Node {
+                id: vertex.id,
+                kind: vertex.kind,
+                inputs: graph.inputs(vertex.id),
+                layer: Layer::new(vs.root() / format!("block_{}", vertex.id), vertex.kind, &vertex.params),
+            })
+            .collect();
+        Ok(Self { vs, nodes, input, input_shape, output, flat, kinds })
+    }
+
+    // parameters of a group are summed up
+    pub fn report(&self) -> api::CompileReport {
+        let mut blocks = Vec::<api::BlockReport>::new();
+        for node in &self.nodes {
+            let id = self.flat.top_level(node.id);
+            let params = node.layer.param_count();
+            match blocks.iter_mut().find(|block| block.id == id) {
+                Some(block) => block.params += params,
+                None => blocks.push(api::BlockReport { id, kind: self.kinds.get(&id).copied().unwrap_or(node.kind), params }),
+            }
+        }
+        api::CompileReport { total_params: blocks.iter().map(|block| block.params).sum(), blocks }
+    }
+
+    // the parameters of a block of the flattened graph
+    pub fn parameters(&self, id: Id) -> Vec<(&'static str, &Tensor)> {
+        self.nodes.iter().find(|node| node.id == id).map_or_else(Vec::new, |node| node.layer.parameters())
+    }
+
+    pub fn sample(&self, input: &api::SampleInput) -> Result<Tensor, api::Error> {
+        sample(self.input, &self.input_shape, input)
+    }
+
+    // of a single sample, without the batch dimension
+    pub fn input_shape(&self) -> &[i64] {
+        &self.input_shape
+    }
+
+    // weights of an earlier build of the same board, stored under the names of the blocks like `block_3.weight`
+    pub fn load_weights(&mut self, path: &Path) -> Result<(), api::Error> {
+        if !path.is_file() {
+            return Err(api::Error::new(format!("the weights file {} does not exist", path.display())));
+        }
+        self.vs.load(path).map_err(|error| api::Error::new(format!("the weights in {} do not fit the model: {error}", path.display())))
+    }
+
+    pub fn save_weights(&self, path: &Path) -> Result<(), api::Error> {
+        self.vs.save(path).map_err(|error| api::Error::new(format!("cannot write {}: {error}", path.display())))
+    }
+
+    // runs every block, errors are attributed to the block that failed
+    pub fn f_forward_all(&self, xs: &Tensor, train: bool) -> Result<BTreeMap<Id, Tensor>, api::Error> {
+        let mut outputs = BTreeMap::<Id, Tensor>::new();
+        for node in &self.nodes {
+            let inputs = match node.id == self.input {
+                true => vec![xs],
+                false => node.inputs.iter().map(|input| &outputs[input]).collect(),
+            };
+            let ys = node.layer
+                .forward(&inputs, train)
+                .map_err(|error| api::Error::at_block(node.id, error))?;
+            outputs.insert(node.id, ys);
+        }
+        Ok(outputs)
+    }
+
+    pub fn f_forward_t(&self, xs: &Tensor, train: bool) -> Result<Tensor, api::Error> {
+        let mut outputs = self.f_forward_all(xs, train)?;
+        Ok(outputs.remove(&self.output).unwrap())
+    }
+
+    pub fn run(&self, input: &api::SampleInput) -> Result<api::ForwardReport, api::Error> {
+        let error = |error| self.flat.top_level_error(error);
+        let xs = self.sample(input).map_err(error)?;
+        let outputs = tch::no_grad(|| self.f_forward_all(&xs, false)).map_err(error)?;
+        let blocks = outputs
+            .iter()
+            .map(|(id, ys)| {
+                let path = self.flat.origin.get(id).cloned().unwrap_or_else(|| vec![*id]);
+                stats(path, ys).map_err(|error| api::Error::at_block(self.flat.top_level(*id), error))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(api::ForwardReport { blocks })
+    }
+}
+
+impl nn::Module for GraphModule {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        self.f_forward_t(xs, false).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl std::fmt::Debug for GraphModule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GraphModule(blocks: {}, input: {}, output: {})", self.nodes.len(), self.input, self.output)
+    }
+}
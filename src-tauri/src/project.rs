@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use common::{api, layer::LayerKind, project::{Project, VERSION}};
+use serde_json::{json, Value};
+
+pub fn save(path: &Path, project: &Project) -> Result<(), api::Error> {
+    let json = serde_json::to_string_pretty(project).map_err(api::Error::new)?;
+    fs::write(path, json).map_err(|error| api::Error::new(format!("cannot write {}: {error}", path.display())))
+}
+
+pub fn load(path: &Path) -> Result<Project, api::Error> {
+    let json = fs::read_to_string(path).map_err(|error| api::Error::new(format!("cannot read {}: {error}", path.display())))?;
+    let mut value = serde_json::from_str::<Value>(&json).map_err(api::Error::new)?;
+    // the version is checked first, so an old file is not reported as a parse error
+    let mut version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| api::Error::new("not a project file: no version"))?;
+    if version == 1 {
+        migrate_v1(&mut value)?;
+        version = 2;
+    }
+    // version 3 added optional groups
+    if version == 2 {
+        value["version"] = json!(3);
+        version = 3;
+    }
+    // version 4 added optional references to library components
+    if version == 3 {
+        value["version"] = json!(4);
+        version = 4;
+    }
+    if version != VERSION as u64 {
+        return Err(api::Error::new(format!("unsupported project version {version}, expected {VERSION}")));
+    }
+    let project = serde_json::from_value::<Project>(value).map_err(api::Error::new)?;
+    project.validate()?;
+    Ok(project)
+}
+
+// version 1 stored arrows as [from, to] pairs, the inputs of a block were ordered by id
+fn migrate_v1(value: &mut Value) -> Result<(), api::Error> {
+    let invalid = || api::Error::new("invalid arrows in a version 1 project");
+    // version 1 had neither groups nor imported ops, so the kind tells the number of inputs
+    let input_counts = value["blocks"]
+        .as_array()
+        .ok_or_else(|| api::Error::new("invalid blocks in a version 1 project"))?
+        .iter()
+        .filter_map(|block| {
+            let kind = serde_json::from_value::<LayerKind>(block["kind"].clone()).ok()?;
+            Some((block["id"].as_i64()?, kind.input_ports().len()))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut edges = value["edges"]
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|edge| match edge.as_array().map(Vec::as_slice) {
+            Some([from, to]) => from.as_i64().zip(to.as_i64()).ok_or_else(invalid),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    edges.sort();
+    let mut next_port = BTreeMap::<i64, usize>::new();
+    let edges = edges
+        .into_iter()
+        .map(|(from, to)| {
+            let port = next_port.entry(to).or_default();
+            *port += 1;
+            json!({ "from": from, "from_port": 0, "to": to, "to_port": *port - 1 })
+        })
+        .collect::<Vec<_>>();
+    // such arrows would only be reported later as going to an unknown port
+    for (block, arrows) in next_port {
+        match input_counts.get(&block) {
+            Some(inputs) if arrows > *inputs => {
+                return Err(api::Error::new(format!("block #{block} of the version 1 project has {arrows} incoming arrows but only {inputs} inputs")));
+            }
+            _ => {}
+        }
+    }
+    value["edges"] = Value::Array(edges);
+    value["version"] = json!(2);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use common::{api, graph::Graph, layer::LayerKind};
+use tch::{CModule, Kind, TchError, Tensor};
+
+use crate::model::{self, GraphModule};
+
+const MODULE_NAME: &str = "Model";
+// enough to read the scores of a classifier
+const REPORTED_VALUES: i64 = 32;
+
+// where the weights of a saved model go, so that a later save of the same board can start from them
+fn weights_path(path: &Path) -> PathBuf {
+    path.with_extension("ot")
+}
+
+// the sample shape of a saved model, a TorchScript file does not tell it
+fn shape_path(path: &Path) -> PathBuf {
+    path.with_extension("shape.json")
+}
+
+// traces the model on a random sample, the weights are the initialized ones unless a weights file is given;
+// the weights and the sample shape are saved next to the model
+pub fn save(graph: &Graph, path: &Path, weights: Option<&Path>) -> Result<(), api::Error> {
+    let mut module = GraphModule::build(graph)?;
+    if let Some(weights) = weights {
+        module.load_weights(weights)?;
+    }
+    // the trace can not report errors, so they are found by a run before it
+    module.run(&api::SampleInput::Random { batch: 1 })?;
+    let xs = module.sample(&api::SampleInput::Random { batch: 1 })?;
+    // the closure is called from libtorch, so an error is kept for after the trace instead of unwinding through it
+    let mut failure = None;
+    let traced = CModule::create_by_tracing(MODULE_NAME, "forward", &[xs], &mut |inputs| {
+        match module.f_forward_t(&inputs[0], false) {
+            Ok(ys) => vec![ys],
+            Err(error) => {
+                failure = Some(error);
+                vec![inputs[0].shallow_clone()]
+            }
+        }
+    });
+    if let Some(error) = failure {
+        return Err(error);
+    }
+    let traced = traced.map_err(|error| api::Error::new(format!("cannot trace the model: {error}")))?;
+    traced.save(path).map_err(|error| api::Error::new(format!("cannot write {}: {error}", path.display())))?;
+    module.save_weights(&weights_path(path))?;
+    let shape_path = shape_path(path);
+    let shape = serde_json::to_string(module.input_shape()).map_err(api::Error::new)?;
+    fs::write(&shape_path, shape).map_err(|error| api::Error::new(format!("cannot write {}: {error}", shape_path.display())))
+}
+
+// runs a saved TorchScript model on a sample of the shape saved with it,
+// models saved elsewhere take the shape of the Input block of the board
+pub fn run(path: &Path, graph: &Graph, input: &api::SampleInput) -> Result<api::InferenceReport, api::Error> {
+    if !path.is_file() {
+        return Err(api::Error::new(format!("the model file {} does not exist", path.display())));
+    }
+    let (block, shape) = match fs::read_to_string(shape_path(path)) {
+        Ok(json) => {
+            let shape = serde_json::from_str::<Vec<i64>>(&json)
+                .map_err(|error| api::Error::new(format!("the sample shape saved with {} does not parse: {error}", path.display())))?;
+            (None, shape)
+        }
+        Err(_) => {
+            let block = model::single_block(graph, LayerKind::Input)?;
+            let shape = graph.vertex(block).and_then(|vertex| vertex.params.dims("shape")).unwrap_or_default();
+            (Some(block), shape.to_vec())
+        }
+    };
+    let xs = model::sample(block.unwrap_or_default(), &shape, input).map_err(|error| api::Error { block, ..error })?;
+    let mut module = CModule::load(path).map_err(|error| api::Error::new(format!("cannot load {}: {error}", path.display())))?;
+    module.set_eval();
+    let ys = tch::no_grad(|| module.forward_ts(&[&xs])).map_err(|error| api::Error {
+        block,
+        message: format!("the model does not take a batch of shape {:?}: {error}", xs.size()),
+    })?;
+    let values = first_values(&ys).map_err(|error| api::Error::new(format!("the model output is not a tensor of numbers: {error}")))?;
+    Ok(api::InferenceReport { shape: ys.size(), values })
+}
+
+fn first_values(ys: &Tensor) -> Result<Vec<f64>, TchError> {
+    let sample = ys.f_get(0)?.f_flatten(0, -1)?.f_to_kind(Kind::Double)?;
+    let count = sample.size()[0].min(REPORTED_VALUES);
+    Vec::<f64>::try_from(&sample.f_narrow(0, 0, count)?)
+}